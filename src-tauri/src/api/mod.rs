@@ -2,6 +2,7 @@ use warp::Filter;
 use std::sync::{Arc, Mutex};
 use crate::profiles::Profile;
 use crate::mods::Mod;
+use crate::game::supervisor::GameSupervisor;
 
 // Shared state to hold current launch info
 // In a real app, this would be updated when the game launches
@@ -9,6 +10,7 @@ use crate::mods::Mod;
 pub struct AppState {
     pub current_profile: Arc<Mutex<Option<Profile>>>,
     pub active_mods: Arc<Mutex<Vec<Mod>>>,
+    pub supervisor: GameSupervisor,
 }
 
 pub async fn start_server(state: AppState) {
@@ -3,7 +3,7 @@ use reqwest::Client;
 use crate::auth::{Account, AccountType};
 use std::fmt;
 
-const CLIENT_ID: &str = "09c31371-2763-4334-824e-73ad0e3f4a58";
+pub(crate) const CLIENT_ID: &str = "09c31371-2763-4334-824e-73ad0e3f4a58";
 const REDIRECT_URI: &str = "http://localhost:8080";
 const SCOPE: &str = "XboxLive.signin offline_access";
 
@@ -67,6 +67,8 @@ pub struct MicrosoftAccount {
     pub refresh_token: String,
     #[serde(rename = "expiresAt")]
     pub expires_at: i64,
+    /// Xbox user id, passed to the game as `${auth_xuid}`
+    pub xuid: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -237,7 +239,10 @@ pub async fn full_login_chain(client: &Client, ms_token_res: TokenResponse) -> R
     println!("DEBUG: XSTS Response keys: {:?}", xsts_data.as_object().map(|o| o.keys().collect::<Vec<_>>()));
 
     let xsts_token = xsts_data["Token"].as_str().ok_or(AuthError::Protocol("No XSTS token".into()))?.to_string();
-    let uhs = xsts_data["DisplayClaims"]["xui"][0]["uhs"].as_str().ok_or(AuthError::Protocol("No UHS".into()))?.to_string();
+    let xui = &xsts_data["DisplayClaims"]["xui"][0];
+    let uhs = xui["uhs"].as_str().ok_or(AuthError::Protocol("No UHS".into()))?.to_string();
+    // Xbox user id for ${auth_xuid}
+    let xuid = xui["xid"].as_str().map(String::from);
 
     println!("DEBUG: UHS length: {}, XSTS Token length: {}", uhs.len(), xsts_token.len());
     
@@ -298,6 +303,9 @@ pub async fn full_login_chain(client: &Client, ms_token_res: TokenResponse) -> R
 
     let profile: MinecraftProfile = serde_json::from_str(&profile_text).map_err(|e| AuthError::Protocol(format!("Profile Parse: {} - Body: {}", e, profile_text)))?;
     
+    // The Minecraft XSTS claims don't always carry the xid; the Minecraft token does
+    let xuid = xuid.or_else(|| token_claim(&mc_data.access_token, "xuid"));
+
     Ok(MicrosoftAccount {
         uuid: profile.id,
         username: profile.name,
@@ -306,9 +314,23 @@ pub async fn full_login_chain(client: &Client, ms_token_res: TokenResponse) -> R
         xbox_token: xsts_token,
         refresh_token: ms_token_res.refresh_token.unwrap_or_default(),
         expires_at: chrono::Utc::now().timestamp() + (mc_data.expires_in as i64),
+        xuid,
     })
 }
 
+/// String claim from a JWT's payload, without verifying it
+fn token_claim(token: &str, claim: &str) -> Option<String> {
+    use base64::Engine;
+    let payload = token.split('.').nth(1)?;
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    match &claims[claim] {
+        serde_json::Value::String(value) => Some(value.clone()),
+        serde_json::Value::Number(value) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
 pub async fn upload_skin(token: &str, file_path: &str, variant: &str) -> Result<(), AuthError> {
     let client = Client::new();
     let file_content = std::fs::read(file_path).map_err(|e| AuthError::Unexpected(e.to_string()))?;
@@ -335,3 +357,17 @@ pub async fn upload_skin(token: &str, file_path: &str, variant: &str) -> Result<
         Err(AuthError::Network(format!("Upload failed: {}", text)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_xuid_from_token() {
+        // {"alg":"none"}.{"xuid":"2535405290000000","uid":"abc"}.
+        let token = "eyJhbGciOiJub25lIn0.eyJ4dWlkIjoiMjUzNTQwNTI5MDAwMDAwMCIsInVpZCI6ImFiYyJ9.";
+        assert_eq!(token_claim(token, "xuid").as_deref(), Some("2535405290000000"));
+        assert_eq!(token_claim(token, "missing"), None);
+        assert_eq!(token_claim("not a jwt", "xuid"), None);
+    }
+}
@@ -19,6 +19,8 @@ pub struct Account {
     pub skin_url: Option<String>,
     pub cape_url: Option<String>,
     pub expires_at: Option<i64>, // Timestamp
+    #[serde(default)]
+    pub xuid: Option<String>, // Xbox user id, passed as ${auth_xuid}
 }
 
 impl Account {
@@ -36,6 +38,7 @@ impl Account {
             skin_url: None,
             cape_url: None,
             expires_at: None,
+            xuid: None,
         }
     }
 }
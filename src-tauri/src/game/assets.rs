@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use reqwest::Client;
+use crate::game::downloader::{DownloadJob, DownloadQueue, FileHash};
+use crate::game::{AssetIndexContent, GameManager, Manifest};
+
+/// Default for `GameManager::assets_base_url`
+pub const ASSETS_BASE_URL: &str = "https://resources.download.minecraft.net";
+
+/// Where the game should look for its assets once they are in place
+#[derive(Debug, Clone)]
+pub struct AssetLayout {
+    /// Value for `${assets_index_name}`
+    pub index_id: String,
+    /// Value for `${assets_root}` (always `assets/`)
+    pub assets_root: PathBuf,
+    /// Value for `${game_assets}`: `assets/virtual/<id>` or `<game_dir>/resources` for legacy
+    /// indexes, otherwise the same as `assets_root`
+    pub game_assets: PathBuf,
+}
+
+impl GameManager {
+    pub fn assets_dir(&self) -> PathBuf {
+        self.base_dir.join("assets")
+    }
+
+    /// Asset index id for a manifest, preferring `assetIndex.id` over the legacy `assets` field
+    pub fn asset_index_id(manifest: &Manifest) -> String {
+        manifest
+            .asset_index
+            .as_ref()
+            .map(|a| a.id.clone())
+            .or_else(|| manifest.assets.clone())
+            .unwrap_or_else(|| "legacy".to_string())
+    }
+
+    /// Resolve the asset layout from the local index without touching the network
+    pub fn asset_layout(&self, manifest: &Manifest, game_dir: &Path) -> AssetLayout {
+        let index_id = Self::asset_index_id(manifest);
+        let assets_root = self.assets_dir();
+        let index = self.read_asset_index(&index_id).ok();
+        let game_assets = match &index {
+            Some(index) if index.map_to_resources => game_dir.join("resources"),
+            Some(index) if index.is_virtual => assets_root.join("virtual").join(&index_id),
+            _ => assets_root.clone(),
+        };
+
+        AssetLayout { index_id, assets_root, game_assets }
+    }
+
+    fn read_asset_index(&self, index_id: &str) -> Result<AssetIndexContent, String> {
+        let index_path = self.assets_dir().join("indexes").join(format!("{}.json", index_id));
+        let content = fs::read_to_string(&index_path).map_err(|e| format!("Failed to read asset index {}: {}", index_id, e))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse asset index {}: {}", index_id, e))
+    }
+
+    /// Make sure the asset index and every object it lists are present.
+    ///
+    /// With `verify_hashes` every existing object is re-hashed (repair); otherwise a size
+    /// check is enough to consider it present, which keeps launches fast.
+    pub async fn ensure_assets(
+        &self,
+        client: &Client,
+        queue: &DownloadQueue,
+        manifest: &Manifest,
+        game_dir: &Path,
+        verify_hashes: bool,
+    ) -> Result<AssetLayout, String> {
+        let assets_dir = self.assets_dir();
+        let objects_dir = assets_dir.join("objects");
+        let index_id = Self::asset_index_id(manifest);
+
+        // 1. Index
+        if let Some(asset_index) = &manifest.asset_index {
+            let index_path = assets_dir.join("indexes").join(format!("{}.json", asset_index.id));
+            let job = DownloadJob::new(&asset_index.url, index_path, Some(FileHash::sha1(&asset_index.sha1)), Some(asset_index.size));
+            queue.run(client, vec![job]).await?;
+        }
+        let index = match self.read_asset_index(&index_id) {
+            Ok(index) => index,
+            // Custom manifests without an assetIndex: nothing we can fetch
+            Err(e) if manifest.asset_index.is_none() => {
+                println!("WARN: {}", e);
+                return Ok(self.asset_layout(manifest, game_dir));
+            }
+            Err(e) => return Err(e),
+        };
+
+        // 2. Objects (deduplicated, many names share the same hash)
+        let mut seen = std::collections::HashSet::new();
+        let mut jobs = Vec::new();
+        for object in index.objects.values() {
+            if object.hash.len() < 2 || !seen.insert(object.hash.clone()) {
+                continue;
+            }
+            let prefix = &object.hash[0..2];
+            let job = DownloadJob::new(
+                format!("{}/{}/{}", self.assets_base_url, prefix, object.hash),
+                objects_dir.join(prefix).join(&object.hash),
+                Some(FileHash::sha1(&object.hash)),
+                Some(object.size),
+            );
+
+            // Repairs leave the hash check to the queue; otherwise a matching size is enough
+            let present = !verify_hashes && fs::metadata(&job.path).map(|m| m.len() == object.size).unwrap_or(false);
+            if !present {
+                jobs.push(job);
+            }
+        }
+
+        if !jobs.is_empty() {
+            println!("DEBUG: Downloading {} asset objects for index {}", jobs.len(), index_id);
+            queue.run(client, jobs).await?;
+        }
+
+        // 3. Legacy layouts: copy objects out by name
+        let layout = self.asset_layout(manifest, game_dir);
+        if index.is_virtual || index.map_to_resources {
+            for (name, object) in &index.objects {
+                if object.hash.len() < 2 {
+                    continue;
+                }
+                let source = objects_dir.join(&object.hash[0..2]).join(&object.hash);
+                let target = layout.game_assets.join(name);
+                let up_to_date = fs::metadata(&target).map(|m| m.len() == object.size).unwrap_or(false);
+                if up_to_date {
+                    continue;
+                }
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                fs::copy(&source, &target).map_err(|e| format!("Failed to copy asset {}: {}", name, e))?;
+            }
+        }
+
+        Ok(layout)
+    }
+}
@@ -0,0 +1,314 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use regex::Regex;
+use crate::mods::Mod;
+
+/// Lines of game output kept for diagnosing launches that die before writing a crash report
+pub const OUTPUT_TAIL_LINES: usize = 200;
+
+/// Most recent game output, shared between the log readers and the exit handler
+pub type OutputTail = Arc<Mutex<VecDeque<String>>>;
+
+pub fn push_tail(tail: &OutputTail, line: &str) {
+    let mut tail = tail.lock().unwrap();
+    if tail.len() == OUTPUT_TAIL_LINES {
+        tail.pop_front();
+    }
+    tail.push_back(line.to_string());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashCause {
+    WrongJava,
+    MissingDependency,
+    MixinFailure,
+    OutOfMemory,
+    GraphicsDriver,
+    DuplicateMod,
+    Unknown,
+}
+
+/// A mod named by the crash, matched to a jar in the mods folder when possible
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuspectMod {
+    pub mod_id: String,
+    pub name: Option<String>,
+    pub filename: Option<String>,
+}
+
+/// Payload of `game_crashed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashDiagnosis {
+    pub instance_id: String,
+    pub version_id: String,
+    pub exit_code: Option<i32>,
+    pub cause: CrashCause,
+    pub summary: String,
+    /// Crash report or hs_err file the diagnosis was made from
+    pub report_path: Option<String>,
+    pub suspect_mods: Vec<SuspectMod>,
+    /// Lines that gave the cause away
+    pub evidence: Vec<String>,
+}
+
+/// Newest crash report or JVM fatal error log written after `since`
+pub fn find_crash_report(game_dir: &Path, since: SystemTime) -> Option<PathBuf> {
+    let mut candidates = Vec::new();
+
+    if let Ok(entries) = fs::read_dir(game_dir.join("crash-reports")) {
+        candidates.extend(entries.flatten().map(|e| e.path()).filter(|p| p.extension().is_some_and(|e| e == "txt")));
+    }
+    if let Ok(entries) = fs::read_dir(game_dir) {
+        candidates.extend(entries.flatten().map(|e| e.path()).filter(|p| {
+            p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("hs_err_pid") && n.ends_with(".log"))
+        }));
+    }
+
+    candidates
+        .into_iter()
+        .filter_map(|p| Some((fs::metadata(&p).and_then(|m| m.modified()).ok()?, p)))
+        .filter(|(modified, _)| *modified >= since)
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, p)| p)
+}
+
+/// Classify a crash from its report (if any) and the last game output, naming mods from `mods`
+pub fn diagnose(
+    instance_id: &str,
+    version_id: &str,
+    exit_code: Option<i32>,
+    report: Option<&Path>,
+    output: &[String],
+    mods: &[Mod],
+) -> CrashDiagnosis {
+    let mut text = report.and_then(|p| fs::read_to_string(p).ok()).unwrap_or_default();
+    text.push('\n');
+    text.push_str(&output.join("\n"));
+
+    let (cause, summary, evidence) = classify(&text);
+    let suspect_mods = suspect_mod_ids(cause, &text)
+        .into_iter()
+        .map(|id| match_mod(&id, mods))
+        .collect();
+
+    CrashDiagnosis {
+        instance_id: instance_id.to_string(),
+        version_id: version_id.to_string(),
+        exit_code,
+        cause,
+        summary,
+        report_path: report.map(|p| p.to_string_lossy().to_string()),
+        suspect_mods,
+        evidence,
+    }
+}
+
+/// (cause, patterns) checked in order; the first hit wins
+const SIGNATURES: &[(CrashCause, &[&str])] = &[
+    (CrashCause::WrongJava, &[
+        "UnsupportedClassVersionError",
+        "has been compiled by a more recent version of the Java Runtime",
+        "cannot be cast to class java.net.URLClassLoader",
+        "Unrecognized VM option",
+        "Unrecognized option:",
+    ]),
+    (CrashCause::DuplicateMod, &[
+        "DuplicateModsFoundException",
+        "Found duplicate mods",
+        "has been loaded more than once",
+    ]),
+    (CrashCause::MissingDependency, &[
+        "Missing or unsupported mandatory dependencies",
+        "which is missing!",
+        "Incompatible mods found!",
+        "ModResolutionException",
+        "Could not find required mod",
+    ]),
+    (CrashCause::MixinFailure, &[
+        "MixinApplyError",
+        "MixinTransformerError",
+        "InvalidMixinException",
+        "Mixin apply failed",
+        "Mixin prepare failed",
+    ]),
+    (CrashCause::OutOfMemory, &[
+        "java.lang.OutOfMemoryError",
+        "Out of Memory Error",
+        "There is insufficient memory for the Java Runtime Environment",
+        // -Xmx larger than the machine (or a 32-bit JVM) can reserve: "... for 8388608KB object heap"
+        "Could not reserve enough space for",
+        "Invalid maximum heap size",
+    ]),
+    (CrashCause::GraphicsDriver, &[
+        "Pixel format not accelerated",
+        "GLFW error 65542",
+        "GLFW error 65543",
+        "No OpenGL context",
+        "Failed to create window",
+        "atio6axx.dll",
+        "atioglxx.dll",
+        "ig9icd64.dll",
+        "ig75icd64.dll",
+        "nvoglv64.dll",
+        "libnvidia-glcore",
+        "org.lwjgl.LWJGLException",
+    ]),
+];
+
+fn classify(text: &str) -> (CrashCause, String, Vec<String>) {
+    for (cause, patterns) in SIGNATURES {
+        let evidence: Vec<String> = text
+            .lines()
+            .filter(|line| patterns.iter().any(|p| line.contains(p)))
+            .map(|line| line.trim().to_string())
+            .take(5)
+            .collect();
+        if !evidence.is_empty() {
+            return (*cause, summary_for(*cause).to_string(), evidence);
+        }
+    }
+
+    // Fall back to the report's own description line
+    let description = text
+        .lines()
+        .find_map(|line| line.strip_prefix("Description: "))
+        .unwrap_or("The game exited unexpectedly");
+    (CrashCause::Unknown, description.trim().to_string(), Vec::new())
+}
+
+fn summary_for(cause: CrashCause) -> &'static str {
+    match cause {
+        CrashCause::WrongJava => "The selected Java version can't run this version of the game or its mod loader",
+        CrashCause::MissingDependency => "A mod is missing a dependency or needs a different version of one",
+        CrashCause::MixinFailure => "A mod failed to apply its mixins, usually an incompatible mod or version",
+        CrashCause::OutOfMemory => "The game ran out of memory or couldn't reserve it; check the maximum memory for this profile",
+        CrashCause::GraphicsDriver => "The graphics driver failed to create an OpenGL context; update your GPU drivers",
+        CrashCause::DuplicateMod => "The same mod is installed more than once",
+        CrashCause::Unknown => "The game exited unexpectedly",
+    }
+}
+
+/// Mod ids the crash text blames, most specific source first
+fn suspect_mod_ids(cause: CrashCause, text: &str) -> Vec<String> {
+    let patterns: &[&str] = match cause {
+        // "Mixin [foo.mixins.json:SomeMixin] from mod foo failed"
+        CrashCause::MixinFailure => &[r"from mod ([a-z0-9_\-]+)", r"\[([a-z0-9_\-]+)\.mixins\.json"],
+        // Fabric: "Mod 'Foo' (foo) 1.0 requires ..."; Forge: "Requested by: 'foo'"
+        CrashCause::MissingDependency => &[r"Mod '[^']*' \(([a-z0-9_\-]+)\)", r"Requested by: '([a-z0-9_\-]+)'"],
+        CrashCause::DuplicateMod => &[r"Mod ID: '([a-z0-9_\-]+)'", r"Mod ID ([a-z0-9_\-]+) has been loaded more than once"],
+        _ => &[],
+    };
+
+    let mut ids: Vec<String> = Vec::new();
+    let mut push = |id: &str| {
+        if id != "minecraft" && id != "java" && !ids.iter().any(|i| i == id) {
+            ids.push(id.to_string());
+        }
+    };
+    for pattern in patterns {
+        let re = Regex::new(pattern).unwrap();
+        for caps in re.captures_iter(text) {
+            push(&caps[1]);
+        }
+    }
+
+    // Fabric and Forge crash reports list their own suspects
+    let suspects = Regex::new(r"Suspected Mods?: ([^\n]+)").unwrap();
+    let id_in_parens = Regex::new(r"\(([a-z0-9_\-]+)\)").unwrap();
+    for caps in suspects.captures_iter(text) {
+        for id in id_in_parens.captures_iter(&caps[1]) {
+            push(&id[1]);
+        }
+    }
+
+    ids
+}
+
+fn match_mod(mod_id: &str, mods: &[Mod]) -> SuspectMod {
+    let found = mods
+        .iter()
+        .find(|m| m.mod_id.as_deref() == Some(mod_id))
+        .or_else(|| mods.iter().find(|m| m.name.eq_ignore_ascii_case(mod_id)))
+        .or_else(|| mods.iter().find(|m| m.filename.to_lowercase().contains(mod_id)));
+
+    SuspectMod {
+        mod_id: mod_id.to_string(),
+        name: found.map(|m| m.name.clone()),
+        filename: found.map(|m| m.filename.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heap_reservation_failure_is_out_of_memory() {
+        let output = "Error occurred during initialization of VM\n\
+            Could not reserve enough space for 8388608KB object heap\n\
+            Error: Could not create the Java Virtual Machine.\n\
+            Error: A fatal exception has occurred. Program will exit.";
+        let (cause, _, evidence) = classify(output);
+        assert_eq!(cause, CrashCause::OutOfMemory);
+        assert_eq!(evidence, ["Could not reserve enough space for 8388608KB object heap"]);
+    }
+
+    #[test]
+    fn unsupported_class_version_is_wrong_java() {
+        let output = "Exception in thread \"main\" java.lang.UnsupportedClassVersionError: net/minecraft/client/main/Main \
+            has been compiled by a more recent version of the Java Runtime (class file version 65.0)\n\
+            Error: Could not create the Java Virtual Machine.";
+        assert_eq!(classify(output).0, CrashCause::WrongJava);
+    }
+
+    #[test]
+    fn fabric_missing_dependency_names_the_mod() {
+        let report = "net.fabricmc.loader.impl.FormattedException: Mod resolution encountered an incompatible mod set!\n\
+            A potential solution has been determined:\n\
+            \t - Install fabric-api, any version.\n\
+            Unmet dependency listing:\n\
+            \t - Mod 'Sodium Extra' (sodium-extra) 0.5.1 requires any version of sodium, which is missing!\n";
+        let (cause, _, _) = classify(report);
+        assert_eq!(cause, CrashCause::MissingDependency);
+        assert_eq!(suspect_mod_ids(cause, report), ["sodium-extra"]);
+    }
+
+    #[test]
+    fn fabric_mixin_failure_names_the_mod() {
+        let report = "---- Minecraft Crash Report ----\n\
+            Description: Initializing game\n\
+            \n\
+            org.spongepowered.asm.mixin.transformer.throwables.MixinTransformerError: An unexpected critical error was encountered\n\
+            Caused by: org.spongepowered.asm.mixin.throwables.MixinApplyError: Mixin [iris.mixins.json:MixinGameRenderer] from mod iris failed injection check\n";
+        let (cause, _, _) = classify(report);
+        assert_eq!(cause, CrashCause::MixinFailure);
+        assert_eq!(suspect_mod_ids(cause, report), ["iris"]);
+    }
+
+    #[test]
+    fn forge_report_lists_suspected_mods() {
+        let report = "---- Minecraft Crash Report ----\n\
+            Description: Rendering overlay\n\
+            \n\
+            java.lang.NullPointerException: Cannot invoke \"Object.getClass()\" because \"value\" is null\n\
+            Suspected Mods: Create (create), Flywheel (flywheel)\n";
+        let (cause, summary, _) = classify(report);
+        assert_eq!(cause, CrashCause::Unknown);
+        assert_eq!(summary, "Rendering overlay");
+        assert_eq!(suspect_mod_ids(cause, report), ["create", "flywheel"]);
+    }
+
+    #[test]
+    fn forge_duplicate_mods() {
+        let report = "net.minecraftforge.fml.loading.DuplicateModsFoundException: Found duplicate mods:\n\
+            \tMod ID: 'jei' from mod files: jei-1.20.1-15.2.0.jar, jei-1.20.1-15.3.0.jar\n";
+        let (cause, _, _) = classify(report);
+        assert_eq!(cause, CrashCause::DuplicateMod);
+        assert_eq!(suspect_mod_ids(cause, report), ["jei"]);
+    }
+}
@@ -1,66 +1,653 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::fs::{self, File};
-use std::io::{self, Write};
-use sha2::{Sha256, Digest};
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
 use reqwest::Client;
 use tokio::io::AsyncWriteExt;
+use tokio::sync::Semaphore;
 
-pub async fn download_file(client: &Client, url: &str, path: &Path, expected_sha256: Option<&str>, app_handle: &tauri::AppHandle) -> Result<(), String> {
-    use tauri::Emitter;
+pub const DEFAULT_CONCURRENCY: usize = 8;
+const DEFAULT_RETRIES: u32 = 3;
+const DEFAULT_EMIT_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/// Expected digest of a file. Mojang metadata uses SHA-1, Adoptium SHA-256 and Modrinth SHA-512.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileHash {
+    pub algorithm: HashAlgorithm,
+    pub value: String,
+}
 
+impl FileHash {
+    pub fn new(algorithm: HashAlgorithm, value: impl Into<String>) -> Self {
+        Self { algorithm, value: value.into().to_lowercase() }
+    }
+
+    pub fn sha1(value: impl Into<String>) -> Self {
+        Self::new(HashAlgorithm::Sha1, value)
+    }
+
+    pub fn sha256(value: impl Into<String>) -> Self {
+        Self::new(HashAlgorithm::Sha256, value)
+    }
+
+    /// True if the file exists and its digest matches
+    pub fn matches_file(&self, path: &Path) -> bool {
+        compute_hash(path, self.algorithm).map(|h| h == self.value).unwrap_or(false)
+    }
+
+    pub fn matches_bytes(&self, bytes: &[u8]) -> bool {
+        hash_bytes(bytes, self.algorithm) == self.value
+    }
+}
+
+/// Temporary path a download is written to before being moved into place
+pub fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// Stream `url` into `path`, calling `on_chunk(chunk_len, content_length)` for every chunk written.
+///
+/// Data goes to a `.part` file first. A leftover `.part` from an interrupted download is
+/// resumed with a `Range` request when the server supports it, and the file is only renamed
+/// into place once its size and hash have been verified.
+async fn fetch_to_path<F>(client: &Client, url: &str, path: &Path, expected_hash: Option<&FileHash>, expected_size: Option<u64>, mut on_chunk: F) -> Result<(), String>
+where
+    F: FnMut(u64, u64),
+{
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
 
-    // Check match... (simplified for brevity, keeping logic)
-    if path.exists() {
-        if let Some(hash) = expected_sha256 {
-            if let Ok(existing_hash) = compute_sha256(path) {
-                if existing_hash == hash { return Ok(()); }
-            }
+    let part = part_path(path);
+    let mut existing = fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+    if expected_size.is_some_and(|size| existing > size) {
+        let _ = fs::remove_file(&part);
+        existing = 0;
+    }
+
+    // A complete .part whose rename failed; asking for `bytes=size-` would only get a 416
+    if existing > 0 && expected_size == Some(existing) {
+        if verify_part(&part, url, expected_hash, expected_size).is_ok() {
+            on_chunk(existing, existing);
+            return fs::rename(&part, path).map_err(|e| e.to_string());
         }
+        existing = 0;
     }
 
-    let mut res = client.get(url).send().await.map_err(|e| e.to_string())?;
+    let mut request = client.get(url);
+    if existing > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing));
+    }
+    let mut res = request.send().await.map_err(|e| e.to_string())?;
+
+    if res.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // Stale or already complete .part that the server can't continue; start over
+        let _ = fs::remove_file(&part);
+        existing = 0;
+        res = client.get(url).send().await.map_err(|e| e.to_string())?;
+    }
     if !res.status().is_success() {
         return Err(format!("Failed to download {}: {}", url, res.status()));
     }
-    
-    let total_size = res.content_length().unwrap_or(0);
-    let mut downloaded: u64 = 0;
 
-    let mut file = tokio::fs::File::create(path).await.map_err(|e| e.to_string())?;
-    
+    // Servers without range support answer 200 with the whole body
+    let resumed = existing > 0 && res.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+    let mut file = if resumed {
+        println!("DEBUG: Resuming {} at {} bytes", url, existing);
+        tokio::fs::OpenOptions::new().append(true).open(&part).await.map_err(|e| e.to_string())?
+    } else {
+        existing = 0;
+        tokio::fs::File::create(&part).await.map_err(|e| e.to_string())?
+    };
+
+    let total_size = res.content_length().map(|len| len + existing).unwrap_or(0);
+    if existing > 0 {
+        on_chunk(existing, total_size);
+    }
+
     while let Some(chunk) = res.chunk().await.map_err(|e| e.to_string())? {
         file.write_all(&chunk).await.map_err(|e| e.to_string())?;
-        downloaded += chunk.len() as u64;
-        
-        // Emit progress
-        if total_size > 0 {
-             let _ = app_handle.emit("download_progress", serde_json::json!({
-                 "file": path.file_name().unwrap_or_default().to_string_lossy(),
-                 "total": total_size,
-                 "downloaded": downloaded,
-                 "percent": (downloaded as f64 / total_size as f64) * 100.0
-             }));
-        }
+        on_chunk(chunk.len() as u64, total_size);
     }
+    file.flush().await.map_err(|e| e.to_string())?;
+    drop(file);
 
-    // Verify hash ...
-    if let Some(hash) = expected_sha256 {
-        let actual_hash = compute_sha256(path)?; 
-        if actual_hash != *hash { 
-             return Err(format!("Hash mismatch for {}: expected {}, got {}", url, hash, actual_hash));
+    verify_part(&part, url, expected_hash, expected_size)?;
+    fs::rename(&part, path).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Check a finished `.part` before it becomes visible, deleting it when it can't be resumed
+fn verify_part(part: &Path, url: &str, expected_hash: Option<&FileHash>, expected_size: Option<u64>) -> Result<(), String> {
+    let actual_size = fs::metadata(part).map(|m| m.len()).map_err(|e| e.to_string())?;
+    if let Some(size) = expected_size {
+        if actual_size != size {
+            if actual_size > size {
+                let _ = fs::remove_file(part);
+            }
+            return Err(format!("Size mismatch for {}: expected {}, got {}", url, size, actual_size));
+        }
+    }
+    if let Some(hash) = expected_hash {
+        let actual_hash = compute_hash(part, hash.algorithm)?;
+        if actual_hash != hash.value {
+             let _ = fs::remove_file(part);
+             return Err(format!("Hash mismatch for {}: expected {}, got {}", url, hash.value, actual_hash));
         }
     }
-
     Ok(())
 }
 
-fn compute_sha256(path: &Path) -> Result<String, String> {
+pub fn compute_hash(path: &Path, algorithm: HashAlgorithm) -> Result<String, String> {
     let mut file = File::open(path).map_err(|e| e.to_string())?;
-    let mut hasher = Sha256::new();
-    io::copy(&mut file, &mut hasher).map_err(|e| e.to_string())?;
-    let hash = hasher.finalize();
-    Ok(hex::encode(hash))
+    let hash = match algorithm {
+        HashAlgorithm::Sha1 => {
+            let mut hasher = Sha1::new();
+            io::copy(&mut file, &mut hasher).map_err(|e| e.to_string())?;
+            hex::encode(hasher.finalize())
+        }
+        HashAlgorithm::Sha256 => {
+            let mut hasher = Sha256::new();
+            io::copy(&mut file, &mut hasher).map_err(|e| e.to_string())?;
+            hex::encode(hasher.finalize())
+        }
+        HashAlgorithm::Sha512 => {
+            let mut hasher = Sha512::new();
+            io::copy(&mut file, &mut hasher).map_err(|e| e.to_string())?;
+            hex::encode(hasher.finalize())
+        }
+    };
+    Ok(hash)
+}
+
+pub fn hash_bytes(bytes: &[u8], algorithm: HashAlgorithm) -> String {
+    match algorithm {
+        HashAlgorithm::Sha1 => hex::encode(Sha1::digest(bytes)),
+        HashAlgorithm::Sha256 => hex::encode(Sha256::digest(bytes)),
+        HashAlgorithm::Sha512 => hex::encode(Sha512::digest(bytes)),
+    }
+}
+
+/// A single file to fetch as part of a batch
+#[derive(Debug, Clone)]
+pub struct DownloadJob {
+    pub url: String,
+    pub path: PathBuf,
+    pub hash: Option<FileHash>,
+    pub size: Option<u64>,
+}
+
+impl DownloadJob {
+    pub fn new(url: impl Into<String>, path: impl Into<PathBuf>, hash: Option<FileHash>, size: Option<u64>) -> Self {
+        Self { url: url.into(), path: path.into(), hash, size }
+    }
+
+    /// Whether the target already exists with the expected hash, or the expected
+    /// size when no hash is known
+    pub fn is_valid(&self) -> bool {
+        match (&self.hash, self.size) {
+            (Some(hash), _) => hash.matches_file(&self.path),
+            (None, Some(size)) => fs::metadata(&self.path).map(|m| m.len() == size).unwrap_or(false),
+            (None, None) => false,
+        }
+    }
+}
+
+/// Aggregated progress for a whole batch, emitted as `download_progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadProgress {
+    pub file: String,
+    /// Files downloaded or already valid; failed ones are in `files_failed`
+    pub files_done: usize,
+    pub files_failed: usize,
+    pub files_total: usize,
+    pub downloaded: u64,
+    pub total: u64,
+    /// Bytes per second since the previous report
+    pub speed: u64,
+    pub percent: f64,
+}
+
+pub type ProgressCallback = Arc<dyn Fn(DownloadProgress) + Send + Sync>;
+
+/// Progress callback that forwards to the frontend `download_progress` event
+pub fn emit_progress(app_handle: tauri::AppHandle) -> ProgressCallback {
+    use tauri::Emitter;
+    Arc::new(move |progress| {
+        let _ = app_handle.emit("download_progress", progress);
+    })
+}
+
+/// Runs a batch of downloads with bounded concurrency, retries and throttled progress
+pub struct DownloadQueue {
+    pub concurrency: usize,
+    pub retries: u32,
+    pub emit_interval: Duration,
+    pub label: String,
+    on_progress: Option<ProgressCallback>,
+}
+
+struct QueueState {
+    files_done: AtomicU64,
+    files_failed: AtomicU64,
+    bytes_done: AtomicU64,
+    current: Mutex<String>,
+}
+
+impl Default for DownloadQueue {
+    fn default() -> Self {
+        Self {
+            concurrency: DEFAULT_CONCURRENCY,
+            retries: DEFAULT_RETRIES,
+            emit_interval: DEFAULT_EMIT_INTERVAL,
+            label: String::new(),
+            on_progress: None,
+        }
+    }
+}
+
+impl DownloadQueue {
+    pub fn new(concurrency: usize) -> Self {
+        Self { concurrency: concurrency.max(1), ..Self::default() }
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    pub fn with_progress(mut self, on_progress: ProgressCallback) -> Self {
+        self.on_progress = Some(on_progress);
+        self
+    }
+
+    /// Download every job, returning the first error after the rest of the batch settles
+    pub async fn run(&self, client: &Client, jobs: Vec<DownloadJob>) -> Result<(), String> {
+        let files_total = jobs.len();
+        let bytes_total: u64 = jobs.iter().filter_map(|j| j.size).sum();
+        let state = Arc::new(QueueState {
+            files_done: AtomicU64::new(0),
+            files_failed: AtomicU64::new(0),
+            bytes_done: AtomicU64::new(0),
+            current: Mutex::new(self.label.clone()),
+        });
+
+        // Reporter: emits at a fixed rate instead of once per chunk
+        let reporter = self.on_progress.clone().map(|cb| {
+            let state = state.clone();
+            let interval = self.emit_interval;
+            tokio::spawn(async move {
+                let mut last_bytes = 0u64;
+                let mut last_tick = Instant::now();
+                loop {
+                    tokio::time::sleep(interval).await;
+                    let bytes = state.bytes_done.load(Ordering::Relaxed);
+                    let elapsed = last_tick.elapsed().as_secs_f64();
+                    let speed = if elapsed > 0.0 { (bytes.saturating_sub(last_bytes) as f64 / elapsed) as u64 } else { 0 };
+                    last_bytes = bytes;
+                    last_tick = Instant::now();
+                    cb(Self::snapshot(&state, files_total, bytes_total, speed));
+                }
+            })
+        });
+
+        let semaphore = Arc::new(Semaphore::new(self.concurrency.max(1)));
+        let mut tasks = tokio::task::JoinSet::new();
+
+        for job in jobs {
+            let permit = semaphore.clone().acquire_owned().await.map_err(|e| e.to_string())?;
+            let client = client.clone();
+            let state = state.clone();
+            let retries = self.retries;
+            tasks.spawn(async move {
+                let result = Self::run_job(&client, &job, retries, &state).await;
+                let counter = if result.is_ok() { &state.files_done } else { &state.files_failed };
+                counter.fetch_add(1, Ordering::Relaxed);
+                drop(permit);
+                result
+            });
+        }
+
+        let mut first_error = None;
+        while let Some(joined) = tasks.join_next().await {
+            let result = joined.map_err(|e| e.to_string()).and_then(|r| r);
+            if let Err(e) = result {
+                println!("WARN: Download failed: {}", e);
+                first_error.get_or_insert(e);
+            }
+        }
+
+        if let Some(handle) = reporter {
+            handle.abort();
+        }
+        if let Some(cb) = &self.on_progress {
+            cb(Self::snapshot(&state, files_total, bytes_total, 0));
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    async fn run_job(client: &Client, job: &DownloadJob, retries: u32, state: &QueueState) -> Result<(), String> {
+        // Already present and valid; hashing large files would stall the runtime's workers
+        let existing = job.clone();
+        if tokio::task::spawn_blocking(move || existing.is_valid()).await.unwrap_or(false) {
+            state.bytes_done.fetch_add(job.size.unwrap_or(0), Ordering::Relaxed);
+            return Ok(());
+        }
+
+        if let Some(name) = job.path.file_name() {
+            *state.current.lock().unwrap() = name.to_string_lossy().to_string();
+        }
+
+        let mut attempt = 0;
+        loop {
+            let attempt_bytes = AtomicU64::new(0);
+            let result = fetch_to_path(client, &job.url, &job.path, job.hash.as_ref(), job.size, |chunk_len, _| {
+                attempt_bytes.fetch_add(chunk_len, Ordering::Relaxed);
+                state.bytes_done.fetch_add(chunk_len, Ordering::Relaxed);
+            }).await;
+
+            match result {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    // Don't count bytes from a failed attempt twice
+                    state.bytes_done.fetch_sub(attempt_bytes.load(Ordering::Relaxed), Ordering::Relaxed);
+                    if attempt >= retries {
+                        return Err(e);
+                    }
+                    let backoff = Duration::from_millis(500 * 2u64.pow(attempt));
+                    println!("DEBUG: Retrying {} in {:?} ({})", job.url, backoff, e);
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    fn snapshot(state: &QueueState, files_total: usize, bytes_total: u64, speed: u64) -> DownloadProgress {
+        let files_done = state.files_done.load(Ordering::Relaxed) as usize;
+        let files_failed = state.files_failed.load(Ordering::Relaxed) as usize;
+        let downloaded = state.bytes_done.load(Ordering::Relaxed);
+        let percent = if bytes_total > 0 {
+            (downloaded as f64 / bytes_total as f64 * 100.0).min(100.0)
+        } else if files_total > 0 {
+            files_done as f64 / files_total as f64 * 100.0
+        } else {
+            100.0
+        };
+
+        DownloadProgress {
+            file: state.current.lock().unwrap().clone(),
+            files_done,
+            files_failed,
+            files_total,
+            downloaded,
+            total: bytes_total,
+            speed,
+            percent,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use warp::Filter;
+
+    type Requests = Arc<Mutex<Vec<Option<String>>>>;
+
+    /// Local server returning `body` for any path and recording each request's `Range` header.
+    /// With `ranges` it answers `bytes=N-` with 206, or 416 past the end; otherwise always 200.
+    fn serve(body: &[u8], ranges: bool) -> (String, Requests) {
+        let body = body.to_vec();
+        let requests = Requests::default();
+        let log = requests.clone();
+        let route = warp::header::optional::<String>("range").map(move |range: Option<String>| {
+            log.lock().unwrap().push(range.clone());
+            let start = range
+                .filter(|_| ranges)
+                .and_then(|r| r.strip_prefix("bytes=")?.strip_suffix('-')?.parse::<usize>().ok());
+            let response = warp::http::Response::builder();
+            match start {
+                Some(start) if start >= body.len() => response.status(416).body(Vec::new()),
+                Some(start) => response.status(206).body(body[start..].to_vec()),
+                None => response.status(200).body(body.clone()),
+            }
+            .unwrap()
+        });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        (format!("http://{}/file.bin", addr), requests)
+    }
+
+    fn temp_file() -> PathBuf {
+        std::env::temp_dir().join(format!("jugelauncher-test-{}", uuid::Uuid::new_v4())).join("file.bin")
+    }
+
+    fn body() -> Vec<u8> {
+        (0..64 * 1024).map(|i| (i % 251) as u8).collect()
+    }
+
+    async fn fetch(url: &str, path: &Path, hash: Option<&FileHash>, size: Option<u64>) -> (Result<(), String>, u64) {
+        let mut reported = 0;
+        let result = fetch_to_path(&Client::new(), url, path, hash, size, |len, _| reported += len).await;
+        (result, reported)
+    }
+
+    fn cleanup(path: &Path) {
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[tokio::test]
+    async fn resumes_partial_download() {
+        let body = body();
+        let (url, requests) = serve(&body, true);
+        let path = temp_file();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(part_path(&path), &body[..1000]).unwrap();
+
+        let hash = FileHash::sha1(hash_bytes(&body, HashAlgorithm::Sha1));
+        let (result, reported) = fetch(&url, &path, Some(&hash), Some(body.len() as u64)).await;
+        result.unwrap();
+        assert_eq!(fs::read(&path).unwrap(), body);
+        assert!(!part_path(&path).exists());
+        assert_eq!(reported, body.len() as u64);
+        assert_eq!(*requests.lock().unwrap(), vec![Some("bytes=1000-".to_string())]);
+        cleanup(&path);
+    }
+
+    #[tokio::test]
+    async fn restarts_when_range_not_satisfiable() {
+        let body = body();
+        let (url, requests) = serve(&body, true);
+        let path = temp_file();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(part_path(&path), &body).unwrap();
+
+        // Without a known size the complete .part can't be recognised up front
+        let (result, _) = fetch(&url, &path, None, None).await;
+        result.unwrap();
+        assert_eq!(fs::read(&path).unwrap(), body);
+        assert_eq!(*requests.lock().unwrap(), vec![Some(format!("bytes={}-", body.len())), None]);
+        cleanup(&path);
+    }
+
+    #[tokio::test]
+    async fn full_response_replaces_partial_file() {
+        let body = body();
+        let (url, requests) = serve(&body, false);
+        let path = temp_file();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(part_path(&path), b"stale bytes").unwrap();
+
+        let (result, reported) = fetch(&url, &path, None, Some(body.len() as u64)).await;
+        result.unwrap();
+        assert_eq!(fs::read(&path).unwrap(), body);
+        assert_eq!(reported, body.len() as u64);
+        assert_eq!(*requests.lock().unwrap(), vec![Some("bytes=11-".to_string())]);
+        cleanup(&path);
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_hash_before_rename() {
+        let body = body();
+        let (url, _) = serve(&body, true);
+        let path = temp_file();
+
+        let hash = FileHash::sha1(hash_bytes(b"other", HashAlgorithm::Sha1));
+        let (result, _) = fetch(&url, &path, Some(&hash), Some(body.len() as u64)).await;
+        assert!(result.unwrap_err().starts_with("Hash mismatch"));
+        assert!(!path.exists());
+        assert!(!part_path(&path).exists());
+        cleanup(&path);
+    }
+
+    #[tokio::test]
+    async fn complete_partial_file_is_renamed_without_request() {
+        let body = body();
+        let (url, requests) = serve(&body, true);
+        let path = temp_file();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(part_path(&path), &body).unwrap();
+
+        let hash = FileHash::sha1(hash_bytes(&body, HashAlgorithm::Sha1));
+        let (result, reported) = fetch(&url, &path, Some(&hash), Some(body.len() as u64)).await;
+        result.unwrap();
+        assert_eq!(fs::read(&path).unwrap(), body);
+        assert_eq!(reported, body.len() as u64);
+        assert!(requests.lock().unwrap().is_empty());
+        cleanup(&path);
+    }
+
+    /// Local server answering each request with `respond(path, request_index)` after `delay`;
+    /// returns its base URL and the most requests it had in flight at once
+    fn serve_with<F>(delay: Duration, respond: F) -> (String, Arc<AtomicUsize>)
+    where
+        F: Fn(&str, usize) -> (u16, Vec<u8>) + Send + Sync + 'static,
+    {
+        let respond = Arc::new(respond);
+        let count = Arc::new(AtomicUsize::new(0));
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let max = max_in_flight.clone();
+        let route = warp::path::tail().and_then(move |tail: warp::path::Tail| {
+            let (respond, count, in_flight, max) = (respond.clone(), count.clone(), in_flight.clone(), max.clone());
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(delay).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                let (status, body) = respond(tail.as_str(), count.fetch_add(1, Ordering::SeqCst));
+                Ok::<_, std::convert::Infallible>(warp::http::Response::builder().status(status).body(body).unwrap())
+            }
+        });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        (format!("http://{}", addr), max_in_flight)
+    }
+
+    fn job(base: &str, dir: &Path, name: &str, body: &[u8]) -> DownloadJob {
+        let hash = FileHash::sha1(hash_bytes(body, HashAlgorithm::Sha1));
+        DownloadJob::new(format!("{}/{}", base, name), dir.join(name), Some(hash), Some(body.len() as u64))
+    }
+
+    /// Queue that records its last progress report
+    fn queue(concurrency: usize, retries: u32) -> (DownloadQueue, Arc<Mutex<Option<DownloadProgress>>>) {
+        let last = Arc::new(Mutex::new(None));
+        let report = last.clone();
+        let mut queue = DownloadQueue::new(concurrency).with_progress(Arc::new(move |progress| {
+            *report.lock().unwrap() = Some(progress);
+        }));
+        queue.retries = retries;
+        (queue, last)
+    }
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("jugelauncher-test-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn limits_concurrent_downloads() {
+        let (base, max_in_flight) = serve_with(Duration::from_millis(100), |_, _| (200, b"data".to_vec()));
+        let dir = temp_dir();
+        let jobs = (0..6).map(|i| job(&base, &dir, &format!("{}.bin", i), b"data")).collect();
+
+        let (queue, last) = queue(2, 0);
+        queue.run(&Client::new(), jobs).await.unwrap();
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+        let progress = last.lock().unwrap().clone().unwrap();
+        assert_eq!((progress.files_done, progress.files_failed, progress.files_total), (6, 0, 6));
+        assert_eq!(progress.downloaded, 24);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn retries_with_backoff() {
+        let body = b"eventually".to_vec();
+        let served = body.clone();
+        let (base, _) = serve_with(Duration::ZERO, move |_, n| if n < 2 { (500, Vec::new()) } else { (200, served.clone()) });
+        let dir = temp_dir();
+
+        let (queue, _) = queue(1, 2);
+        let started = Instant::now();
+        queue.run(&Client::new(), vec![job(&base, &dir, "file.bin", &body)]).await.unwrap();
+        // 500 ms, then 1 s
+        assert!(started.elapsed() >= Duration::from_millis(1500));
+        assert_eq!(fs::read(dir.join("file.bin")).unwrap(), body);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn failed_attempt_bytes_are_not_counted() {
+        let body = b"the real contents".to_vec();
+        let served = body.clone();
+        let (base, _) = serve_with(Duration::ZERO, move |_, n| if n == 0 { (200, b"corrupted contents".to_vec()) } else { (200, served.clone()) });
+        let dir = temp_dir();
+
+        let (queue, last) = queue(1, 1);
+        queue.run(&Client::new(), vec![job(&base, &dir, "file.bin", &body)]).await.unwrap();
+        let progress = last.lock().unwrap().clone().unwrap();
+        assert_eq!((progress.downloaded, progress.total), (body.len() as u64, body.len() as u64));
+        assert_eq!(progress.percent, 100.0);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn returns_error_after_batch_settles() {
+        let (base, _) = serve_with(Duration::ZERO, |path, _| if path == "missing.bin" { (404, Vec::new()) } else { (200, b"data".to_vec()) });
+        let slow_base = serve_with(Duration::from_millis(300), |_, _| (200, b"data".to_vec())).0;
+        let dir = temp_dir();
+        let jobs = vec![
+            job(&base, &dir, "missing.bin", b"data"),
+            job(&slow_base, &dir, "slow-1.bin", b"data"),
+            job(&slow_base, &dir, "slow-2.bin", b"data"),
+        ];
+
+        let (queue, last) = queue(3, 0);
+        let error = queue.run(&Client::new(), jobs).await.unwrap_err();
+        assert!(error.contains("404"), "{}", error);
+        assert!(dir.join("slow-1.bin").exists() && dir.join("slow-2.bin").exists());
+        let progress = last.lock().unwrap().clone().unwrap();
+        assert_eq!((progress.files_done, progress.files_failed, progress.files_total), (2, 1, 3));
+        let _ = fs::remove_dir_all(dir);
+    }
 }
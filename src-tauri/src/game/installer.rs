@@ -0,0 +1,276 @@
+use serde::{Deserialize, Serialize};
+use reqwest::Client;
+use crate::game::downloader::{DownloadJob, DownloadQueue, FileHash};
+use crate::game::{GameManager, Manifest};
+
+/// Official Mojang version list. Can be swapped for a local stand-in when testing.
+pub const VERSION_MANIFEST_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionManifest {
+    pub latest: LatestVersions,
+    pub versions: Vec<VersionEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatestVersions {
+    pub release: String,
+    pub snapshot: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionEntry {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub version_type: String,
+    pub url: String,
+    pub time: Option<String>,
+    #[serde(rename = "releaseTime")]
+    pub release_time: Option<String>,
+    pub sha1: Option<String>,
+    #[serde(rename = "complianceLevel")]
+    pub compliance_level: Option<u32>,
+}
+
+/// Fetch and parse a `version_manifest_v2.json` style version list
+pub async fn fetch_version_manifest(client: &Client, url: &str) -> Result<VersionManifest, String> {
+    let res = client.get(url).send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(format!("Failed to fetch version manifest: {}", res.status()));
+    }
+    res.json().await.map_err(|e| format!("Failed to parse version manifest: {}", e))
+}
+
+impl GameManager {
+    /// Install a version from a Mojang-style version manifest: version JSON, client jar,
+    /// libraries (including natives) and assets.
+    pub async fn install_version(
+        &self,
+        client: &Client,
+        queue: &DownloadQueue,
+        manifest_url: &str,
+        version_id: &str,
+    ) -> Result<Manifest, String> {
+        // 1. Resolve the version in the version list
+        let version_list = fetch_version_manifest(client, manifest_url).await?;
+        let entry = version_list
+            .versions
+            .iter()
+            .find(|v| v.id == version_id)
+            .ok_or_else(|| format!("Version {} not found in version manifest", version_id))?;
+
+        // 2. Fetch the version JSON and store it as versions/<id>/<id>.json
+        let version_dir = self.base_dir.join("versions").join(&entry.id);
+        let json_path = version_dir.join(format!("{}.json", entry.id));
+        let res = client.get(&entry.url).send().await.map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            return Err(format!("Failed to fetch version {}: {}", entry.id, res.status()));
+        }
+        let content = res.text().await.map_err(|e| e.to_string())?;
+        if let Some(sha1) = &entry.sha1 {
+            if !FileHash::sha1(sha1).matches_bytes(content.as_bytes()) {
+                return Err(format!("Hash mismatch for version {} JSON", entry.id));
+            }
+        }
+        let manifest: Manifest = serde_json::from_str(&content).map_err(|e| format!("Failed to parse manifest: {}", e))?;
+
+        tokio::fs::create_dir_all(&version_dir).await.map_err(|e| e.to_string())?;
+        tokio::fs::write(&json_path, &content).await.map_err(|e| e.to_string())?;
+
+        // 3. Client jar, libraries and natives
+        self.download_version_files(client, queue, &manifest).await?;
+
+        // 4. Asset index and objects
+        self.ensure_assets(client, queue, &manifest, &self.base_dir, false).await?;
+
+        println!("DEBUG: Installed version {}", manifest.id);
+        Ok(manifest)
+    }
+
+    /// Re-verify an installed version against the hashes in its JSON and re-download
+    /// anything missing or corrupted
+    pub async fn repair_version(&self, client: &Client, queue: &DownloadQueue, version_id: &str) -> Result<Manifest, String> {
+        let manifest = self.load_manifest(version_id).await?;
+        self.download_version_files(client, queue, &manifest).await?;
+        self.ensure_assets(client, queue, &manifest, &self.base_dir, true).await?;
+        println!("DEBUG: Repaired version {}", manifest.id);
+        Ok(manifest)
+    }
+
+    async fn download_version_files(&self, client: &Client, queue: &DownloadQueue, manifest: &Manifest) -> Result<(), String> {
+        let mut jobs = Vec::new();
+
+        if let Some(client_artifact) = manifest.downloads.as_ref().and_then(|d| d.client.as_ref()) {
+            if let Some(url) = &client_artifact.url {
+                let jar_name = manifest.jar.as_ref().unwrap_or(&manifest.id);
+                let jar_path = self.base_dir.join("versions").join(jar_name).join(format!("{}.jar", jar_name));
+                jobs.push(DownloadJob::new(url, jar_path, client_artifact.sha1.as_ref().map(FileHash::sha1), client_artifact.size));
+            }
+        }
+
+        jobs.extend(self.library_downloads(manifest));
+        jobs.extend(self.logging_config_job(manifest));
+
+        // Files that are already present and intact are skipped by the queue
+        queue.run(client, jobs).await
+    }
+
+    /// Collect download jobs for every library and native classifier that applies to this OS
+    pub fn library_downloads(&self, manifest: &Manifest) -> Vec<DownloadJob> {
+        let lib_dir = self.base_dir.join("libraries");
+        let mut downloads = Vec::new();
+
+        for lib in manifest.libraries.iter().flatten() {
+            if !self.should_include_library(lib) {
+                continue;
+            }
+
+            if let Some(lib_downloads) = &lib.downloads {
+                if let Some(artifact) = &lib_downloads.artifact {
+                    if let (Some(url), Some(path)) = (&artifact.url, &artifact.path) {
+                        if !url.is_empty() {
+                            downloads.push(DownloadJob::new(url, lib_dir.join(path), artifact.sha1.as_ref().map(FileHash::sha1), artifact.size));
+                        }
+                    }
+                }
+
+                if let Some(classifier) = Self::native_classifier(lib) {
+                    let native = lib_downloads.classifiers.as_ref().and_then(|c| c.get(&classifier));
+                    if let Some(artifact) = native {
+                        if let (Some(url), Some(path)) = (&artifact.url, &artifact.path) {
+                            downloads.push(DownloadJob::new(url, lib_dir.join(path), artifact.sha1.as_ref().map(FileHash::sha1), artifact.size));
+                        }
+                    }
+                }
+            } else if let Some(repo) = &lib.url {
+                // Maven repository style (Fabric/Quilt): url is the repository root
+                let maven_path = Self::maven_to_path(&lib.name);
+                let url = format!("{}/{}", repo.trim_end_matches('/'), maven_path);
+                downloads.push(DownloadJob::new(url, lib_dir.join(&maven_path), None, None));
+            }
+        }
+
+        downloads
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::downloader::{hash_bytes, HashAlgorithm};
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::{Arc, RwLock};
+    use warp::Filter;
+
+    type Files = Arc<RwLock<HashMap<String, Vec<u8>>>>;
+
+    /// Local stand-in for Mojang's servers, serving `files` by path; returns its base URL
+    fn serve(files: Files) -> String {
+        let route = warp::path::tail().map(move |tail: warp::path::Tail| {
+            let body = files.read().unwrap().get(tail.as_str()).cloned();
+            let status = if body.is_some() { 200 } else { 404 };
+            warp::http::Response::builder().status(status).body(body.unwrap_or_default()).unwrap()
+        });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        format!("http://{}", addr)
+    }
+
+    fn sha1(bytes: &[u8]) -> String {
+        hash_bytes(bytes, HashAlgorithm::Sha1)
+    }
+
+    #[tokio::test]
+    async fn installs_version_from_local_server() {
+        let files = Files::default();
+        let base = serve(files.clone());
+
+        let jar = b"client jar".to_vec();
+        let library = b"library jar".to_vec();
+        let object = b"{\"language.name\": \"English\"}".to_vec();
+        let object_hash = sha1(&object);
+        let asset_index = serde_json::to_vec(&json!({
+            "objects": { "minecraft/lang/en_us.json": { "hash": object_hash, "size": object.len() } }
+        }))
+        .unwrap();
+        let version = serde_json::to_vec(&json!({
+            "id": "1.20.1",
+            "type": "release",
+            "mainClass": "net.minecraft.client.main.Main",
+            "downloads": {
+                "client": { "url": format!("{}/client.jar", base), "sha1": sha1(&jar), "size": jar.len() }
+            },
+            "libraries": [{
+                "name": "com.example:lib:1.0",
+                "downloads": {
+                    "artifact": {
+                        "path": "com/example/lib/1.0/lib-1.0.jar",
+                        "url": format!("{}/maven/com/example/lib/1.0/lib-1.0.jar", base),
+                        "sha1": sha1(&library),
+                        "size": library.len()
+                    }
+                }
+            }],
+            "assetIndex": {
+                "id": "5",
+                "sha1": sha1(&asset_index),
+                "size": asset_index.len(),
+                "url": format!("{}/indexes/5.json", base)
+            }
+        }))
+        .unwrap();
+        let version_manifest = serde_json::to_vec(&json!({
+            "latest": { "release": "1.20.1", "snapshot": "1.20.1" },
+            "versions": [{
+                "id": "1.20.1",
+                "type": "release",
+                "url": format!("{}/v1/packages/1.20.1.json", base),
+                "sha1": sha1(&version)
+            }]
+        }))
+        .unwrap();
+
+        {
+            let mut files = files.write().unwrap();
+            files.insert("mc/game/version_manifest_v2.json".to_string(), version_manifest);
+            files.insert("v1/packages/1.20.1.json".to_string(), version.clone());
+            files.insert("client.jar".to_string(), jar.clone());
+            files.insert("maven/com/example/lib/1.0/lib-1.0.jar".to_string(), library.clone());
+            files.insert("indexes/5.json".to_string(), asset_index);
+            files.insert(format!("assets/{}/{}", &object_hash[..2], object_hash), object.clone());
+        }
+
+        let dir = std::env::temp_dir().join(format!("jugelauncher-test-{}", uuid::Uuid::new_v4()));
+        let game = GameManager::new(dir.clone()).with_assets_base_url(format!("{}/assets", base));
+        let manifest_url = format!("{}/mc/game/version_manifest_v2.json", base);
+        let result = game.install_version(&Client::new(), &DownloadQueue::default(), &manifest_url, "1.20.1").await;
+
+        let read = |path: &str| std::fs::read(dir.join(path)).ok();
+        let installed = (
+            read("versions/1.20.1/1.20.1.json"),
+            read("versions/1.20.1/1.20.1.jar"),
+            read("libraries/com/example/lib/1.0/lib-1.0.jar"),
+            read(&format!("assets/objects/{}/{}", &object_hash[..2], object_hash)),
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(result.unwrap().id, "1.20.1");
+        assert_eq!(installed, (Some(version), Some(jar), Some(library), Some(object)));
+    }
+
+    #[tokio::test]
+    async fn unknown_version_is_an_error() {
+        let files = Files::default();
+        let base = serve(files.clone());
+        files.write().unwrap().insert(
+            "version_manifest_v2.json".to_string(),
+            serde_json::to_vec(&json!({ "latest": { "release": "1.20.1", "snapshot": "1.20.1" }, "versions": [] })).unwrap(),
+        );
+
+        let game = GameManager::new(std::env::temp_dir().join(format!("jugelauncher-test-{}", uuid::Uuid::new_v4())));
+        let manifest_url = format!("{}/version_manifest_v2.json", base);
+        let err = game.install_version(&Client::new(), &DownloadQueue::default(), &manifest_url, "1.99").await.unwrap_err();
+        assert_eq!(err, "Version 1.99 not found in version manifest");
+    }
+}
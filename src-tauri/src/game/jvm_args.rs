@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+
+/// Named JVM tuning presets a profile can pick instead of hand-written flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JvmPreset {
+    /// G1 with the flags the official launcher uses
+    G1,
+    /// Aikar's G1 tuning, popular for heavily modded packs
+    Aikar,
+    /// Generational ZGC, Java 21+
+    ZgcGenerational,
+    /// Serial GC and smaller code cache for machines with little RAM
+    LowMemory,
+}
+
+const G1_ARGS: &[&str] = &[
+    "-XX:+UnlockExperimentalVMOptions",
+    "-XX:+UseG1GC",
+    "-XX:G1NewSizePercent=20",
+    "-XX:G1ReservePercent=20",
+    "-XX:MaxGCPauseMillis=50",
+    "-XX:G1HeapRegionSize=32M",
+];
+
+const AIKAR_ARGS: &[&str] = &[
+    "-XX:+UseG1GC",
+    "-XX:+ParallelRefProcEnabled",
+    "-XX:MaxGCPauseMillis=200",
+    "-XX:+UnlockExperimentalVMOptions",
+    "-XX:+DisableExplicitGC",
+    "-XX:+AlwaysPreTouch",
+    "-XX:G1NewSizePercent=30",
+    "-XX:G1MaxNewSizePercent=40",
+    "-XX:G1HeapRegionSize=8M",
+    "-XX:G1ReservePercent=20",
+    "-XX:G1HeapWastePercent=5",
+    "-XX:G1MixedGCCountTarget=4",
+    "-XX:InitiatingHeapOccupancyPercent=15",
+    "-XX:G1MixedGCLiveThresholdPercent=90",
+    "-XX:G1RSetUpdatingPauseTimePercent=5",
+    "-XX:SurvivorRatio=32",
+    "-XX:+PerfDisableSharedMem",
+    "-XX:MaxTenuringThreshold=1",
+];
+
+const LOW_MEMORY_ARGS: &[&str] = &["-XX:+UseSerialGC", "-XX:ReservedCodeCacheSize=64m", "-Xss512k"];
+
+/// Flags that only exist from a given Java major version on
+const VERSIONED_FLAGS: &[(&str, u32)] = &[
+    ("-XX:+ZGenerational", 21),
+    ("-XX:+UseZGC", 15),
+    ("-XX:+UseShenandoahGC", 12),
+    ("-XX:+UseCompactObjectHeaders", 24),
+    ("--enable-native-access", 17),
+    ("--add-opens", 9),
+    ("--add-exports", 9),
+];
+
+impl JvmPreset {
+    pub fn min_java_version(self) -> u32 {
+        match self {
+            JvmPreset::ZgcGenerational => 21,
+            _ => 8,
+        }
+    }
+
+    /// Flags for this preset on the given Java major version
+    pub fn args(self, java_major: u32) -> Result<Vec<String>, String> {
+        if java_major < self.min_java_version() {
+            return Err(format!("The {:?} JVM preset needs Java {} or newer, but Java {} is selected", self, self.min_java_version(), java_major));
+        }
+
+        let args = match self {
+            JvmPreset::G1 => G1_ARGS.to_vec(),
+            JvmPreset::Aikar => AIKAR_ARGS.to_vec(),
+            // Generational is the only ZGC mode from Java 23 on, where the flag is deprecated
+            JvmPreset::ZgcGenerational if java_major >= 23 => vec!["-XX:+UseZGC"],
+            JvmPreset::ZgcGenerational => vec!["-XX:+UseZGC", "-XX:+ZGenerational"],
+            JvmPreset::LowMemory => LOW_MEMORY_ARGS.to_vec(),
+        };
+        Ok(args.into_iter().map(String::from).collect())
+    }
+}
+
+/// Preset flags followed by the profile's own flags, checked against the Java that will run them.
+/// With a preset the preset's collector wins over a `-XX:+Use*GC` in `java_args`, since the JVM
+/// refuses to start with two.
+pub fn build_jvm_args(preset: Option<JvmPreset>, java_args: &str, java_major: u32) -> Result<Vec<String>, String> {
+    let mut custom = parse_java_args(java_args)?;
+    let mut args = match preset {
+        Some(preset) => {
+            custom.retain(|arg| {
+                let is_gc = arg.starts_with("-XX:+Use") && arg.ends_with("GC");
+                if is_gc {
+                    println!("WARN: Ignoring {} from the profile, the {:?} preset picks the collector", arg, preset);
+                }
+                !is_gc
+            });
+            preset.args(java_major)?
+        }
+        None => Vec::new(),
+    };
+    args.extend(custom);
+
+    let unsupported = unsupported_args(&args, java_major);
+    if !unsupported.is_empty() {
+        return Err(format!("Java {} does not support these JVM arguments: {}", java_major, unsupported.join(" ")));
+    }
+    Ok(args)
+}
+
+/// Split a profile's `java_args` like a shell would, so quoted values with spaces survive
+pub fn parse_java_args(java_args: &str) -> Result<Vec<String>, String> {
+    let args = if cfg!(target_os = "windows") { split_windows_args(java_args) } else { shlex::split(java_args) };
+    args.ok_or_else(|| format!("Invalid quoting in JVM arguments: {}", java_args))
+}
+
+/// Split on whitespace outside quotes, keeping backslashes as they are since on Windows
+/// they are path separators rather than escapes
+fn split_windows_args(java_args: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current: Option<String> = None;
+    let mut quote = None;
+
+    for c in java_args.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.get_or_insert_with(String::new).push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                current.get_or_insert_with(String::new);
+            }
+            None if c.is_whitespace() => args.extend(current.take()),
+            None => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+
+    if quote.is_some() {
+        return None;
+    }
+    args.extend(current);
+    Some(args)
+}
+
+/// Flags in `args` that the given Java major version doesn't understand
+pub fn unsupported_args(args: &[String], java_major: u32) -> Vec<String> {
+    args.iter()
+        .filter(|arg| {
+            VERSIONED_FLAGS
+                .iter()
+                .any(|(flag, min)| arg.starts_with(flag) && java_major < *min)
+        })
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn expands_presets() {
+        assert_eq!(build_jvm_args(Some(JvmPreset::G1), "", 17).unwrap(), strings(G1_ARGS));
+        assert_eq!(build_jvm_args(Some(JvmPreset::LowMemory), "-Xss1m", 8).unwrap(), strings(&["-XX:+UseSerialGC", "-XX:ReservedCodeCacheSize=64m", "-Xss512k", "-Xss1m"]));
+        assert_eq!(build_jvm_args(Some(JvmPreset::ZgcGenerational), "", 21).unwrap(), strings(&["-XX:+UseZGC", "-XX:+ZGenerational"]));
+        assert_eq!(build_jvm_args(Some(JvmPreset::ZgcGenerational), "", 23).unwrap(), strings(&["-XX:+UseZGC"]));
+        assert!(build_jvm_args(Some(JvmPreset::ZgcGenerational), "", 17).is_err());
+    }
+
+    #[test]
+    fn preset_replaces_profile_collector() {
+        let args = build_jvm_args(Some(JvmPreset::Aikar), "-XX:+UseParallelGC -XX:+UseShenandoahGC -Dfml.ignorePatchDiscrepancies=true", 17).unwrap();
+        let mut expected = strings(AIKAR_ARGS);
+        expected.push("-Dfml.ignorePatchDiscrepancies=true".to_string());
+        assert_eq!(args, expected);
+
+        // Without a preset the profile's collector is kept
+        assert_eq!(build_jvm_args(None, "-XX:+UseParallelGC", 17).unwrap(), strings(&["-XX:+UseParallelGC"]));
+    }
+
+    #[test]
+    fn flags_unsupported_by_java_version() {
+        let args = strings(&["--add-opens=java.base/java.lang=ALL-UNNAMED", "-XX:+UseZGC", "-XX:+ZGenerational", "-Xmx2G"]);
+        assert_eq!(unsupported_args(&args, 8), args[..3].to_vec());
+        assert_eq!(unsupported_args(&args, 17), args[2..3].to_vec());
+        assert!(unsupported_args(&args, 21).is_empty());
+        assert!(build_jvm_args(None, "-XX:+UseShenandoahGC", 8).is_err());
+    }
+
+    #[test]
+    fn keeps_quoted_paths_together() {
+        assert_eq!(
+            parse_java_args("-Xmx2G \"-Djava.io.tmpdir=/tmp/my game\" '-Dname=Steve Jobs'").unwrap(),
+            strings(&["-Xmx2G", "-Djava.io.tmpdir=/tmp/my game", "-Dname=Steve Jobs"])
+        );
+        assert!(parse_java_args("-Dpath=\"/tmp/unterminated").is_err());
+    }
+
+    #[test]
+    fn windows_paths_keep_backslashes() {
+        assert_eq!(
+            split_windows_args("-Djava.io.tmpdir=\"C:\\Program Files\\Temp\\\" -Dlog=C:\\logs\\mc.log  '-Dnatives=D:\\My Games\\natives'").unwrap(),
+            strings(&["-Djava.io.tmpdir=C:\\Program Files\\Temp\\", "-Dlog=C:\\logs\\mc.log", "-Dnatives=D:\\My Games\\natives"])
+        );
+        assert_eq!(split_windows_args("-Dempty=\"\" -Xmx1G").unwrap(), strings(&["-Dempty=", "-Xmx1G"]));
+        assert!(split_windows_args("\"C:\\Program Files").is_none());
+    }
+}
@@ -1,8 +1,13 @@
 use crate::auth::Account;
-use crate::game::Manifest;
-use std::io::{BufRead, BufReader};
+use crate::game::rules::Features;
+use crate::game::assets::AssetLayout;
+use crate::game::logs::GameLog;
+use crate::game::{ArgumentValue, Manifest, RuleContext};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::process::{Child, Command, Stdio};
 use tauri::Emitter;
 
 pub struct LaunchOptions {
@@ -11,6 +16,190 @@ pub struct LaunchOptions {
     pub java_path: String, // Changed to String to make it easier
     pub width: u32,
     pub height: u32,
+    /// Working directory and `--gameDir`; the shared .minecraft or the profile's instance directory
+    pub game_dir: PathBuf,
+    /// Preset and profile JVM flags, added after the memory settings so they can override them
+    pub jvm_args: Vec<String>,
+    pub full_screen: bool,
+    pub demo: bool,
+    /// World, server or realm to open straight after the game starts
+    pub quick_play: Option<QuickPlay>,
+}
+
+/// Quick-play target, stored on profiles and passed as `--quickPlay*` (or `--server` before 1.20)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuickPlay {
+    /// Folder name of a world in `saves/`
+    Singleplayer { world: String },
+    Multiplayer { host: String, port: Option<u16> },
+    Realm { realm_id: String },
+}
+
+impl QuickPlay {
+    /// Parse `host`, `host:port` or `[ipv6]:port`
+    pub fn server(address: &str) -> Result<Self, String> {
+        let address = address.trim();
+        let invalid = || format!("Invalid server address: {}", address);
+        let (host, port) = match address.strip_prefix('[') {
+            Some(rest) => {
+                let (host, rest) = rest.split_once(']').ok_or_else(invalid)?;
+                (host, rest.strip_prefix(':'))
+            }
+            // A bare IPv6 address has more than one colon and no port
+            None if address.matches(':').count() > 1 => (address, None),
+            None => match address.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (address, None),
+            },
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+        let port = port.map(|p| p.parse::<u16>().map_err(|_| invalid())).transpose()?;
+        Ok(QuickPlay::Multiplayer { host: host.to_string(), port })
+    }
+
+    /// Reject targets saved with an empty world, host or realm id
+    pub fn validate(&self) -> Result<(), String> {
+        let missing = match self {
+            QuickPlay::Singleplayer { world } if world.trim().is_empty() => "world",
+            QuickPlay::Multiplayer { host, .. } if host.trim().is_empty() => "server address",
+            QuickPlay::Realm { realm_id } if realm_id.trim().is_empty() => "realm id",
+            _ => return Ok(()),
+        };
+        Err(format!("Quick play needs a {}", missing))
+    }
+
+    /// `host:port` as the game expects it
+    fn address(host: &str, port: Option<u16>) -> String {
+        let host = if host.contains(':') { format!("[{}]", host) } else { host.to_string() };
+        match port {
+            Some(port) => format!("{}:{}", host, port),
+            None => host,
+        }
+    }
+}
+
+pub const LAUNCHER_NAME: &str = "JugeLauncher";
+pub const CLASSPATH_SEPARATOR: &str = if cfg!(target_os = "windows") { ";" } else { ":" };
+
+/// JVM arguments the official launcher uses for versions without `arguments.jvm`
+const LEGACY_JVM_ARGS: &[&str] = &["-Djava.library.path=${natives_directory}", "-cp", "${classpath}"];
+
+/// Game arguments for manifests that define neither `minecraftArguments` nor `arguments.game`
+const FALLBACK_GAME_ARGS: &[&str] = &[
+    "--username", "${auth_player_name}",
+    "--version", "${version_name}",
+    "--gameDir", "${game_directory}",
+    "--assetsDir", "${assets_root}",
+    "--assetIndex", "${assets_index_name}",
+    "--uuid", "${auth_uuid}",
+    "--accessToken", "${auth_access_token}",
+    "--userType", "${user_type}",
+];
+
+/// `${name}` substitutions shared by JVM and game arguments
+pub struct Placeholders {
+    values: HashMap<&'static str, String>,
+}
+
+impl Placeholders {
+    pub fn new(
+        base_dir: &Path,
+        manifest: &Manifest,
+        account: &Account,
+        options: &LaunchOptions,
+        assets: &AssetLayout,
+        natives_dir: &Path,
+        classpath: &str,
+    ) -> Self {
+        let access_token = account.access_token.clone().unwrap_or_else(|| "0".to_string());
+        let user_type = if account.account_type == crate::auth::AccountType::Microsoft {
+            "msa"
+        } else {
+            "mojang"
+        };
+        let game_dir = options.game_dir.to_string_lossy().to_string();
+
+        let mut values = HashMap::new();
+        values.insert("auth_player_name", account.name.clone());
+        values.insert("auth_uuid", account.uuid.clone());
+        values.insert("auth_access_token", access_token.clone());
+        values.insert("auth_session", format!("token:{}:{}", access_token, account.uuid));
+        values.insert("auth_xuid", account.xuid.clone().unwrap_or_else(|| "0".to_string()));
+        values.insert("clientid", crate::auth::microsoft::CLIENT_ID.to_string());
+        values.insert("user_type", user_type.to_string());
+        values.insert("user_properties", "{}".to_string());
+        values.insert("version_name", manifest.id.clone());
+        values.insert("version_type", manifest.version_type.clone().unwrap_or_else(|| "release".to_string()));
+        values.insert("game_directory", game_dir.clone());
+        values.insert("game_dir", game_dir);
+        values.insert("assets_root", assets.assets_root.to_string_lossy().to_string());
+        values.insert("game_assets", assets.game_assets.to_string_lossy().to_string());
+        values.insert("assets_index_name", assets.index_id.clone());
+        values.insert("resolution_width", options.width.to_string());
+        values.insert("resolution_height", options.height.to_string());
+        values.insert("natives_directory", natives_dir.to_string_lossy().to_string());
+        values.insert("library_directory", base_dir.join("libraries").to_string_lossy().to_string());
+        values.insert("classpath", classpath.to_string());
+        values.insert("classpath_separator", CLASSPATH_SEPARATOR.to_string());
+        values.insert("launcher_name", LAUNCHER_NAME.to_string());
+        values.insert("launcher_version", env!("CARGO_PKG_VERSION").to_string());
+
+        Self { values }
+    }
+
+    pub fn set(&mut self, key: &'static str, value: impl Into<String>) {
+        self.values.insert(key, value.into());
+    }
+
+    /// Replace every known `${name}` in `arg`; unknown placeholders are left untouched
+    pub fn resolve(&self, arg: &str) -> String {
+        let mut out = String::with_capacity(arg.len());
+        let mut rest = arg;
+        while let Some(start) = rest.find("${") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            match after.find('}') {
+                Some(end) => {
+                    let key = &after[..end];
+                    match self.values.get(key) {
+                        Some(value) => out.push_str(value),
+                        None => out.push_str(&rest[start..start + 3 + end]),
+                    }
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    out.push_str(&rest[start..]);
+                    rest = "";
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// The manifest's JVM arguments, plus the natives path and classpath from `LEGACY_JVM_ARGS`
+/// when it doesn't set them itself (legacy versions, custom lists such as `["-Dtest=true"]`)
+pub fn manifest_jvm_args(manifest: &Manifest, ctx: &RuleContext) -> Vec<String> {
+    let mut args = manifest
+        .arguments
+        .as_ref()
+        .and_then(|a| a.jvm.as_ref())
+        .map(|jvm| ArgumentValue::resolve_all(jvm, ctx))
+        .unwrap_or_default();
+
+    let has_natives = args.iter().any(|arg| arg.contains("-Djava.library.path="));
+    let has_classpath = args.iter().any(|arg| arg.contains("${classpath}") || arg == "-cp" || arg == "-classpath");
+    for arg in LEGACY_JVM_ARGS {
+        let missing = if arg.starts_with("-Djava.library.path=") { !has_natives } else { !has_classpath };
+        if missing {
+            args.push(arg.to_string());
+        }
+    }
+    args
 }
 
 pub fn launch_game(
@@ -18,18 +207,24 @@ pub fn launch_game(
     manifest: &Manifest,
     account: &Account,
     options: &LaunchOptions,
+    game_log: &Arc<GameLog>,
     app: tauri::AppHandle,
-) -> Result<(), String> {
+) -> Result<Child, String> {
     let version_dir = base_dir.join("versions").join(&manifest.id);
     let lib_dir = base_dir.join("libraries");
     let native_dir = version_dir.join("natives");
 
-    println!("DEBUG: Launching {} from {:?}", manifest.id, base_dir);
+    println!("DEBUG: Launching {} from {:?} in {:?}", manifest.id, base_dir, options.game_dir);
     println!("DEBUG: Version dir: {:?}", version_dir);
 
     // 1. Build Classpath using new library resolution
     let game_manager = crate::game::GameManager::new(base_dir.to_path_buf());
-    let lib_paths = game_manager.get_library_paths(manifest);
+    let assets = game_manager.asset_layout(manifest, &options.game_dir);
+    let libraries = game_manager.resolve_libraries(manifest);
+    for dropped in &libraries.dropped {
+        println!("WARN: Dropped duplicate library {}", dropped);
+        let _ = app.emit("game_log", format!("[LAUNCHER] Dropped duplicate library {}", dropped));
+    }
 
     let mut classpath = Vec::new();
 
@@ -42,7 +237,7 @@ pub fn launch_game(
     classpath.push(main_jar);
 
     // Add all libraries
-    for lib_path in lib_paths {
+    for lib_path in libraries.paths {
         if lib_path.exists() {
             classpath.push(lib_path);
         } else {
@@ -50,16 +245,11 @@ pub fn launch_game(
         }
     }
 
-    let cp_sep = if cfg!(target_os = "windows") {
-        ";"
-    } else {
-        ":"
-    };
     let cp_str = classpath
         .iter()
         .map(|p| p.to_string_lossy())
         .collect::<Vec<_>>()
-        .join(cp_sep);
+        .join(CLASSPATH_SEPARATOR);
 
     // 2. Resolve Java Path
     let java = PathBuf::from(&options.java_path);
@@ -71,114 +261,93 @@ pub fn launch_game(
 
     // 3. Build Arguments
     let mut cmd = Command::new(java);
-    cmd.current_dir(base_dir); // Set working directory
+    cmd.current_dir(&options.game_dir); // Set working directory
+
+    let mut vars = Placeholders::new(base_dir, manifest, account, options, &assets, &native_dir, &cp_str);
+
+    // Versions from 1.20 on declare quick play as conditional game arguments
+    let game_args = manifest.arguments.as_ref().and_then(|a| a.game.as_ref());
+    let supports_quick_play = game_args.is_some_and(|args| ArgumentValue::uses_feature(args, "is_quick_play_multiplayer"));
+    let mut features = Features {
+        has_custom_resolution: true,
+        is_demo_user: options.demo,
+        ..Features::default()
+    };
+    let mut legacy_args: Vec<String> = Vec::new();
+    // Profiles saved before targets were validated may still hold empty ones
+    let quick_play = options.quick_play.as_ref().filter(|quick_play| match quick_play.validate() {
+        Ok(()) => true,
+        Err(e) => {
+            println!("WARN: Ignoring quick play target: {}", e);
+            false
+        }
+    });
+    match quick_play {
+        Some(QuickPlay::Singleplayer { world }) if supports_quick_play => {
+            features.is_quick_play_singleplayer = true;
+            vars.set("quickPlaySingleplayer", world.clone());
+        }
+        Some(QuickPlay::Multiplayer { host, port }) if supports_quick_play => {
+            features.is_quick_play_multiplayer = true;
+            vars.set("quickPlayMultiplayer", QuickPlay::address(host, *port));
+        }
+        Some(QuickPlay::Realm { realm_id }) if supports_quick_play => {
+            features.is_quick_play_realms = true;
+            vars.set("quickPlayRealms", realm_id.clone());
+        }
+        Some(QuickPlay::Multiplayer { host, port }) => {
+            legacy_args.extend(["--server".to_string(), host.clone(), "--port".to_string(), port.unwrap_or(25565).to_string()]);
+        }
+        Some(quick_play) => println!("WARN: {} does not support quick play, ignoring {:?}", manifest.id, quick_play),
+        None => {}
+    }
+
+    // Conditional manifest arguments are evaluated against this OS and launcher features
+    let demo_in_manifest = game_args.is_some_and(|args| ArgumentValue::uses_feature(args, "is_demo_user"));
+    let rule_ctx = RuleContext::current(features);
 
     // JVM Args
     cmd.arg(format!("-Xms{}M", options.min_memory));
     cmd.arg(format!("-Xmx{}M", options.max_memory));
-    cmd.arg(format!(
-        "-Djava.library.path={}",
-        native_dir.to_string_lossy()
-    ));
-    cmd.arg("-cp").arg(cp_str);
-
-    // Add custom JVM args from manifest if available
-    if let Some(arguments) = &manifest.arguments {
-        if let Some(jvm_args) = &arguments.jvm {
-            for arg in jvm_args {
-                match arg {
-                    crate::game::ArgumentValue::String(s) => {
-                        cmd.arg(s);
-                    }
-                    _ => {
-                        // Skip complex conditional arguments
-                    }
-                }
-            }
-        }
+    cmd.args(&options.jvm_args);
+
+    for arg in &manifest_jvm_args(manifest, &rule_ctx) {
+        cmd.arg(vars.resolve(arg));
     }
 
     // Main Class
     cmd.arg(&manifest.main_class);
 
-    // Game Args
-    // Helper closure to replace placeholders
-    let replace_args = |s: &str| -> String {
-        s.replace("${auth_player_name}", &account.name)
-            .replace("${version_name}", &manifest.id)
-            .replace("${game_directory}", base_dir.to_string_lossy().as_ref())
-            .replace(
-                "${assets_root}",
-                base_dir.join("assets").to_string_lossy().as_ref(),
-            )
-            .replace(
-                "${game_assets}",
-                base_dir.join("assets").to_string_lossy().as_ref(),
-            )
-            .replace(
-                "${assets_index_name}",
-                manifest.assets.as_deref().unwrap_or("legacy"),
-            )
-            .replace("${auth_uuid}", &account.uuid)
-            .replace(
-                "${auth_access_token}",
-                account.access_token.as_deref().unwrap_or("0"),
-            )
-            .replace(
-                "${user_type}",
-                if account.account_type == crate::auth::AccountType::Microsoft {
-                    "msa"
-                } else {
-                    "mojang"
-                },
-            )
-            .replace("${user_properties}", "{}")
-            .replace("${version_type}", "release")
-    };
-
     // Build game arguments
     // Try minecraft_arguments first (old format like Forge 1.8.9)
     if let Some(mc_args) = &manifest.minecraft_arguments {
         // Old format: space-separated string
         for arg in mc_args.split_whitespace() {
-            cmd.arg(replace_args(arg));
+            cmd.arg(vars.resolve(arg));
         }
-    } else if let Some(arguments) = &manifest.arguments {
-        // New format: array of strings/objects
-        if let Some(game_args) = &arguments.game {
-            for arg in game_args {
-                match arg {
-                    crate::game::ArgumentValue::String(s) => {
-                        cmd.arg(replace_args(s));
-                    }
-                    _ => {
-                        // Skip complex conditional arguments for now
-                    }
-                }
-            }
+    } else if let Some(game_args) = game_args {
+        // New format: array of strings/objects, resolution comes from has_custom_resolution
+        for arg in ArgumentValue::resolve_all(game_args, &rule_ctx) {
+            cmd.arg(vars.resolve(&arg));
         }
     } else {
         // Fallback: add standard args manually
-        cmd.arg("--username").arg(&account.name);
-        cmd.arg("--version").arg(&manifest.id);
-        cmd.arg("--gameDir").arg(base_dir);
-        cmd.arg("--assetsDir").arg(base_dir.join("assets"));
-        cmd.arg("--assetIndex")
-            .arg(manifest.assets.as_deref().unwrap_or("legacy"));
-        cmd.arg("--uuid").arg(&account.uuid);
-        cmd.arg("--accessToken")
-            .arg(account.access_token.as_deref().unwrap_or("0"));
-        cmd.arg("--userType").arg(
-            if account.account_type == crate::auth::AccountType::Microsoft {
-                "msa"
-            } else {
-                "mojang"
-            },
-        );
+        for arg in FALLBACK_GAME_ARGS {
+            cmd.arg(vars.resolve(arg));
+        }
     }
 
-    cmd.arg("--width").arg(options.width.to_string());
-    cmd.arg("--height").arg(options.height.to_string());
+    if manifest.arguments.as_ref().and_then(|a| a.game.as_ref()).is_none() || manifest.minecraft_arguments.is_some() {
+        cmd.arg("--width").arg(options.width.to_string());
+        cmd.arg("--height").arg(options.height.to_string());
+    }
+    if options.demo && !demo_in_manifest {
+        cmd.arg("--demo");
+    }
+    if options.full_screen {
+        cmd.arg("--fullscreen");
+    }
+    cmd.args(&legacy_args);
 
     println!(
         "DEBUG: Command arguments: {:?}",
@@ -198,28 +367,82 @@ pub fn launch_game(
     println!("DEBUG: Process spawned with PID: {:?}", child.id());
 
     // Stream logs
-    let stdout = child.stdout.take().unwrap();
-    let stderr = child.stderr.take().unwrap();
-
-    let app_clone = app.clone();
-    std::thread::spawn(move || {
-        let reader = BufReader::new(stdout);
-        for line in reader.lines() {
-            if let Ok(l) = line {
-                let _ = app_clone.emit("game_log", l);
-            }
-        }
-    });
+    game_log.attach(child.stdout.take().unwrap(), false);
+    game_log.attach(child.stderr.take().unwrap(), true);
 
-    let app_clone = app.clone();
-    std::thread::spawn(move || {
-        let reader = BufReader::new(stderr);
-        for line in reader.lines() {
-            if let Ok(l) = line {
-                let _ = app_clone.emit("game_log", format!("[ERROR] {}", l));
-            }
-        }
-    });
+    Ok(child)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders() -> Placeholders {
+        let mut values = HashMap::new();
+        values.insert("auth_player_name", "Steve".to_string());
+        values.insert("natives_directory", "/mc/natives".to_string());
+        values.insert("auth_xuid", "2535405290000000".to_string());
+        Placeholders { values }
+    }
+
+    #[test]
+    fn resolves_known_placeholders() {
+        let p = placeholders();
+        assert_eq!(p.resolve("${auth_player_name}"), "Steve");
+        assert_eq!(p.resolve("-Djava.library.path=${natives_directory}"), "-Djava.library.path=/mc/natives");
+        assert_eq!(p.resolve("${auth_player_name}:${auth_xuid}"), "Steve:2535405290000000");
+        assert_eq!(p.resolve("--demo"), "--demo");
+    }
+
+    #[test]
+    fn leaves_unknown_placeholders() {
+        let p = placeholders();
+        assert_eq!(p.resolve("${x}"), "${x}");
+        assert_eq!(p.resolve("--quickPlayPath ${quickPlayPath} ${auth_player_name}"), "--quickPlayPath ${quickPlayPath} Steve");
+        assert_eq!(p.resolve("${}"), "${}");
+    }
 
-    Ok(())
+    fn jvm_args_for(manifest: serde_json::Value) -> Vec<String> {
+        let manifest: Manifest = serde_json::from_value(manifest).unwrap();
+        manifest_jvm_args(&manifest, &RuleContext::default())
+    }
+
+    #[test]
+    fn custom_jvm_list_gets_natives_and_classpath() {
+        let manifest: Manifest = serde_json::from_str(include_str!("../../../examples/manifest.json")).unwrap();
+        assert_eq!(
+            manifest_jvm_args(&manifest, &RuleContext::default()),
+            ["-Dtest=true", "-Djava.library.path=${natives_directory}", "-cp", "${classpath}"]
+        );
+    }
+
+    #[test]
+    fn legacy_manifest_gets_default_jvm_args() {
+        let args = jvm_args_for(serde_json::json!({ "id": "1.8.9", "minecraftArguments": "--username ${auth_player_name}" }));
+        assert_eq!(args, LEGACY_JVM_ARGS);
+    }
+
+    #[test]
+    fn modern_jvm_args_are_kept_as_is() {
+        let jvm = ["-Djava.library.path=${natives_directory}", "-Dminecraft.launcher.brand=${launcher_name}", "-cp", "${classpath}"];
+        let args = jvm_args_for(serde_json::json!({ "id": "1.20.1", "arguments": { "jvm": jvm } }));
+        assert_eq!(args, jvm);
+    }
+
+    #[test]
+    fn rejects_empty_quick_play_targets() {
+        assert!(QuickPlay::Singleplayer { world: " ".to_string() }.validate().is_err());
+        assert!(QuickPlay::Multiplayer { host: String::new(), port: Some(25565) }.validate().is_err());
+        assert!(QuickPlay::Realm { realm_id: String::new() }.validate().is_err());
+        assert!(QuickPlay::Singleplayer { world: "New World".to_string() }.validate().is_ok());
+        assert!(QuickPlay::server("play.example.com:25566").unwrap().validate().is_ok());
+    }
+
+    #[test]
+    fn leaves_unterminated_placeholders() {
+        let p = placeholders();
+        assert_eq!(p.resolve("${auth_player_name"), "${auth_player_name");
+        assert_eq!(p.resolve("${auth_player_name}-${"), "Steve-${");
+        assert_eq!(p.resolve("$auth_player_name}"), "$auth_player_name}");
+    }
 }
@@ -0,0 +1,180 @@
+use std::cmp::Ordering;
+use std::path::PathBuf;
+use reqwest::Client;
+use crate::game::downloader::{DownloadJob, DownloadQueue, FileHash};
+use crate::game::{compare_versions, GameManager, LoggingFile, Manifest};
+
+/// Lookups in messages are off with this flag from log4j 2.10 on (1.17–1.18)
+pub const NO_LOOKUPS_ARG: &str = "-Dlog4j2.formatMsgNoLookups=true";
+
+/// Mojang's patched configs for versions whose log4j predates the flag
+const PATCHED_CONFIG_17_111: (&str, &str) = (
+    "log4j2_17-111.xml",
+    "https://launcher.mojang.com/v1/objects/4bb89a97a66f350bc9f73b3ca8509632682aea2e/log4j2_17-111.xml",
+);
+const PATCHED_CONFIG_112_116: (&str, &str) = (
+    "log4j2_112-116.xml",
+    "https://launcher.mojang.com/v1/objects/02937d122c86ce73319ef9975b58896fc1b491d1/log4j2_112-116.xml",
+);
+
+/// How a version has to be launched to be safe from Log4Shell (CVE-2021-44228)
+#[derive(Debug, Clone)]
+pub enum Log4ShellMitigation {
+    /// No log4j, or log4j 2.17+
+    NotNeeded,
+    /// log4j 2.10–2.16: disable lookups with a system property
+    NoLookups,
+    /// log4j before 2.10 ignores the property; swap in Mojang's patched config
+    PatchedConfig(LoggingFile),
+}
+
+impl GameManager {
+    pub fn log_configs_dir(&self) -> PathBuf {
+        self.assets_dir().join("log_configs")
+    }
+
+    /// Download job for the version's own `logging.client` config
+    pub fn logging_config_job(&self, manifest: &Manifest) -> Option<DownloadJob> {
+        let config = manifest.logging.as_ref()?.client.as_ref()?;
+        Some(self.log_config_job(&config.file))
+    }
+
+    fn log_config_job(&self, file: &LoggingFile) -> DownloadJob {
+        DownloadJob::new(&file.url, self.log_configs_dir().join(&file.id), file.sha1.as_ref().map(FileHash::sha1), file.size)
+    }
+
+    /// Make sure the logging config is present and return the JVM arguments that apply it,
+    /// including the Log4Shell mitigation for affected versions
+    pub async fn ensure_logging_config(&self, client: &Client, queue: &DownloadQueue, manifest: &Manifest) -> Result<Vec<String>, String> {
+        let mut args = Vec::new();
+        let official = manifest.logging.as_ref().and_then(|l| l.client.as_ref());
+
+        let (file, argument) = match log4shell_mitigation(manifest) {
+            Log4ShellMitigation::PatchedConfig(patched) => {
+                println!("DEBUG: Using patched log4j config {} for {}", patched.id, manifest.id);
+                (Some(patched), "-Dlog4j.configurationFile=${path}".to_string())
+            }
+            mitigation => {
+                if matches!(mitigation, Log4ShellMitigation::NoLookups) {
+                    args.push(NO_LOOKUPS_ARG.to_string());
+                }
+                (official.map(|c| c.file.clone()), official.map(|c| c.argument.clone()).unwrap_or_default())
+            }
+        };
+
+        if let Some(file) = file {
+            let job = self.log_config_job(&file);
+            let path = job.path.clone();
+            queue.run(client, vec![job]).await?;
+            args.push(argument.replace("${path}", &path.to_string_lossy()));
+        }
+
+        Ok(args)
+    }
+}
+
+/// Pick the mitigation from the log4j-core version on the classpath
+pub fn log4shell_mitigation(manifest: &Manifest) -> Log4ShellMitigation {
+    let Some(version) = manifest
+        .libraries
+        .iter()
+        .flatten()
+        .find(|lib| lib.name.starts_with("org.apache.logging.log4j:log4j-core:"))
+        .map(|lib| lib.version().to_string())
+    else {
+        return Log4ShellMitigation::NotNeeded;
+    };
+
+    if compare_versions(&version, "2.17") != Ordering::Less {
+        Log4ShellMitigation::NotNeeded
+    } else if compare_versions(&version, "2.10") != Ordering::Less {
+        Log4ShellMitigation::NoLookups
+    } else {
+        // 1.7–1.11 ship 2.0-beta9, 1.12–1.16 ship 2.8.1
+        let (id, url) = if compare_versions(&version, "2.1") == Ordering::Less { PATCHED_CONFIG_17_111 } else { PATCHED_CONFIG_112_116 };
+        let sha1 = url.split('/').rev().nth(1).map(String::from);
+        Log4ShellMitigation::PatchedConfig(LoggingFile { id: id.to_string(), sha1, size: None, url: url.to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::downloader::{hash_bytes, HashAlgorithm};
+    use serde_json::json;
+    use warp::Filter;
+
+    fn manifest_with_log4j(version: &str) -> Manifest {
+        serde_json::from_value(json!({
+            "id": "test",
+            "libraries": [
+                { "name": "org.apache.logging.log4j:log4j-api:".to_string() + version },
+                { "name": "org.apache.logging.log4j:log4j-core:".to_string() + version }
+            ]
+        }))
+        .unwrap()
+    }
+
+    fn patched_config(version: &str) -> LoggingFile {
+        match log4shell_mitigation(&manifest_with_log4j(version)) {
+            Log4ShellMitigation::PatchedConfig(file) => file,
+            other => panic!("expected a patched config for log4j {}, got {:?}", version, other),
+        }
+    }
+
+    #[test]
+    fn beta9_uses_config_17_111() {
+        let file = patched_config("2.0-beta9");
+        assert_eq!(file.id, "log4j2_17-111.xml");
+        assert_eq!(file.sha1.as_deref(), Some("4bb89a97a66f350bc9f73b3ca8509632682aea2e"));
+    }
+
+    #[test]
+    fn log4j_2_8_uses_config_112_116() {
+        let file = patched_config("2.8.1");
+        assert_eq!(file.id, "log4j2_112-116.xml");
+        assert_eq!(file.sha1.as_deref(), Some("02937d122c86ce73319ef9975b58896fc1b491d1"));
+    }
+
+    #[test]
+    fn log4j_2_14_disables_lookups() {
+        assert!(matches!(log4shell_mitigation(&manifest_with_log4j("2.14.1")), Log4ShellMitigation::NoLookups));
+    }
+
+    #[test]
+    fn fixed_or_missing_log4j_needs_nothing() {
+        assert!(matches!(log4shell_mitigation(&manifest_with_log4j("2.17.1")), Log4ShellMitigation::NotNeeded));
+        let manifest: Manifest = serde_json::from_value(json!({ "id": "b1.7.3" })).unwrap();
+        assert!(matches!(log4shell_mitigation(&manifest), Log4ShellMitigation::NotNeeded));
+    }
+
+    #[tokio::test]
+    async fn rejects_config_with_wrong_sha1() {
+        let route = warp::path("client-1.12.xml").map(|| "<Configuration/>");
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        let mut manifest = manifest_with_log4j("2.17.1");
+        manifest.logging = serde_json::from_value(json!({
+            "client": {
+                "argument": "-Dlog4j.configurationFile=${path}",
+                "file": {
+                    "id": "client-1.12.xml",
+                    "sha1": hash_bytes(b"something else", HashAlgorithm::Sha1),
+                    "url": format!("http://{}/client-1.12.xml", addr)
+                },
+                "type": "log4j2-xml"
+            }
+        }))
+        .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("jugelauncher-test-{}", uuid::Uuid::new_v4()));
+        let game = GameManager::new(dir.clone());
+        let mut queue = DownloadQueue::new(1);
+        queue.retries = 0;
+
+        assert!(game.ensure_logging_config(&Client::new(), &queue, &manifest).await.is_err());
+        assert!(!game.log_configs_dir().join("client-1.12.xml").exists());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
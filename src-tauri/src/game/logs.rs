@@ -0,0 +1,327 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{BufRead, BufReader, LineWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use regex::Regex;
+use tauri::Emitter;
+use crate::game::crash::{push_tail, OutputTail};
+
+/// Launch logs kept per instance; older ones are deleted when a new launch starts
+pub const MAX_LOG_FILES: usize = 10;
+
+/// One line or log4j event of game output, payload of `game_log_record`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub instance_id: String,
+    /// Milliseconds since the epoch, when the game reported one
+    pub timestamp: Option<i64>,
+    /// Time of day as printed on a plain `[12:34:56] [thread/LEVEL]` line, which has no date
+    #[serde(default)]
+    pub time: Option<String>,
+    pub level: String,
+    pub logger: Option<String>,
+    pub thread: Option<String>,
+    pub message: String,
+    pub throwable: Option<String>,
+}
+
+impl LogRecord {
+    /// `[12:34:56] [Render thread/INFO]: message`, the same shape the game prints itself
+    pub fn to_line(&self) -> String {
+        let time = self
+            .timestamp
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .map(|t| t.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+            .or_else(|| self.time.clone());
+        let mut line = match (time, &self.thread) {
+            (Some(time), Some(thread)) => format!("[{}] [{}/{}]: {}", time, thread, self.level, self.message),
+            (None, Some(thread)) => format!("[{}/{}]: {}", thread, self.level, self.message),
+            _ => self.message.clone(),
+        };
+        if let Some(throwable) = &self.throwable {
+            line.push('\n');
+            line.push_str(throwable.trim_end());
+        }
+        line
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogFile {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub modified: i64,
+}
+
+/// Output of one launch: streamed to the UI, kept in the crash tail and written to `logs/`
+pub struct GameLog {
+    instance_id: String,
+    path: Option<PathBuf>,
+    file: Mutex<Option<LineWriter<fs::File>>>,
+    tail: OutputTail,
+    app: tauri::AppHandle,
+}
+
+impl GameLog {
+    /// Open `<logs_dir>/<instance>-<timestamp>.log`, dropping the oldest logs of the instance
+    pub fn create(logs_dir: &Path, instance_id: &str, tail: OutputTail, app: tauri::AppHandle) -> Arc<Self> {
+        let prefix = format!("{}-", sanitize(instance_id));
+        rotate(logs_dir, &prefix);
+
+        let path = logs_dir.join(format!("{}{}.log", prefix, chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")));
+        let file = fs::create_dir_all(logs_dir).and_then(|_| fs::File::create(&path));
+        let (path, file) = match file {
+            Ok(file) => (Some(path), Some(LineWriter::new(file))),
+            Err(e) => {
+                println!("WARN: Could not create game log {:?}: {}", path, e);
+                (None, None)
+            }
+        };
+
+        Arc::new(Self {
+            instance_id: instance_id.to_string(),
+            path,
+            file: Mutex::new(file),
+            tail,
+            app,
+        })
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Read a process stream on its own thread until it closes
+    pub fn attach<R: Read + Send + 'static>(self: &Arc<Self>, stream: R, is_stderr: bool) {
+        let log = self.clone();
+        std::thread::spawn(move || {
+            let mut event = String::new();
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                // log4j XML events span several lines
+                if !event.is_empty() || line.trim_start().starts_with("<log4j:Event") {
+                    event.push_str(&line);
+                    event.push('\n');
+                    if line.contains("</log4j:Event>") {
+                        match parse_log4j_event(&event, &log.instance_id) {
+                            Some(record) => log.record(record),
+                            None => log.record(plain_record(&log.instance_id, &event, is_stderr)),
+                        }
+                        event.clear();
+                    }
+                    continue;
+                }
+                log.record(plain_record(&log.instance_id, &line, is_stderr));
+            }
+            if !event.is_empty() {
+                log.record(plain_record(&log.instance_id, &event, is_stderr));
+            }
+            if let Some(file) = log.file.lock().unwrap().as_mut() {
+                let _ = file.flush();
+            }
+        });
+    }
+
+    fn record(&self, record: LogRecord) {
+        let line = record.to_line();
+        for part in line.lines() {
+            push_tail(&self.tail, part);
+        }
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            let _ = writeln!(file, "{}", line);
+        }
+
+        let display = if record.level == "ERROR" && record.thread.is_none() { format!("[ERROR] {}", line) } else { line };
+        let _ = self.app.emit("game_log", display);
+        let _ = self.app.emit("game_log_record", &record);
+    }
+}
+
+/// Saved launch logs, newest first
+pub fn list_log_files(logs_dir: &Path) -> Vec<LogFile> {
+    let mut files: Vec<LogFile> = fs::read_dir(logs_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                // launcher.log shares the directory but isn't a game log
+                .filter(|e| e.path().extension().is_some_and(|ext| ext == "log") && e.file_name() != "launcher.log")
+                .filter_map(|e| {
+                    let meta = e.metadata().ok()?;
+                    let modified = meta.modified().ok()?.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs() as i64;
+                    Some(LogFile {
+                        name: e.file_name().to_string_lossy().to_string(),
+                        path: e.path().to_string_lossy().to_string(),
+                        size: meta.len(),
+                        modified,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort_by_key(|f| std::cmp::Reverse(f.modified));
+    files
+}
+
+/// Parse a saved launch log back into records for filtering and search
+pub fn read_log_file(logs_dir: &Path, name: &str) -> Result<Vec<LogRecord>, String> {
+    if name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid log file name: {}", name));
+    }
+    let content = fs::read_to_string(logs_dir.join(name)).map_err(|e| e.to_string())?;
+    let instance_id = name.split_once('-').map(|(p, _)| p).unwrap_or(name);
+
+    let mut records: Vec<LogRecord> = Vec::new();
+    for line in content.lines() {
+        let record = plain_record(instance_id, line, false);
+        match records.last_mut() {
+            // Stack trace lines belong to the record above
+            Some(last) if record.thread.is_none() && (line.starts_with('\t') || line.starts_with("Caused by:")) => {
+                let throwable = last.throwable.get_or_insert_with(String::new);
+                throwable.push_str(line);
+                throwable.push('\n');
+            }
+            _ => records.push(record),
+        }
+    }
+    Ok(records)
+}
+
+/// Parse one `<log4j:Event>` from the XMLLayout the version's logging config selects
+pub fn parse_log4j_event(xml: &str, instance_id: &str) -> Option<LogRecord> {
+    static EVENT: OnceLock<Regex> = OnceLock::new();
+    static ATTR: OnceLock<Regex> = OnceLock::new();
+    static MESSAGE: OnceLock<Regex> = OnceLock::new();
+    static THROWABLE: OnceLock<Regex> = OnceLock::new();
+    let event = EVENT.get_or_init(|| Regex::new(r"<log4j:Event\s([^>]*)>").unwrap());
+    let attr = ATTR.get_or_init(|| Regex::new(r#"(\w+)="([^"]*)""#).unwrap());
+    let message = MESSAGE.get_or_init(|| Regex::new(r"(?s)<log4j:Message>(?:<!\[CDATA\[(.*?)\]\]>|(.*?))</log4j:Message>").unwrap());
+    let throwable = THROWABLE.get_or_init(|| Regex::new(r"(?s)<log4j:Throwable>(?:<!\[CDATA\[(.*?)\]\]>|(.*?))</log4j:Throwable>").unwrap());
+
+    let attrs = event.captures(xml)?;
+    let mut record = LogRecord {
+        instance_id: instance_id.to_string(),
+        timestamp: None,
+        time: None,
+        level: "INFO".to_string(),
+        logger: None,
+        thread: None,
+        message: String::new(),
+        throwable: None,
+    };
+    for caps in attr.captures_iter(&attrs[1]) {
+        let value = unescape(&caps[2]);
+        match &caps[1] {
+            "timestamp" => record.timestamp = value.parse().ok(),
+            "level" => record.level = value,
+            "logger" => record.logger = Some(value),
+            "thread" => record.thread = Some(value),
+            _ => {}
+        }
+    }
+
+    let body = |re: &Regex| re.captures(xml).and_then(|c| c.get(1).or_else(|| c.get(2))).map(|m| m.as_str().to_string());
+    record.message = body(message).unwrap_or_default();
+    record.throwable = body(throwable);
+    Some(record)
+}
+
+/// A plain output line; `[time] [thread/LEVEL]: message` lines keep their level and thread
+fn plain_record(instance_id: &str, line: &str, is_stderr: bool) -> LogRecord {
+    static PLAIN: OnceLock<Regex> = OnceLock::new();
+    let plain = PLAIN.get_or_init(|| Regex::new(r"^\[([^\]]+)\] \[([^\]]+)/([A-Z]+)\]: (.*)$").unwrap());
+
+    let line = line.trim_end();
+    match plain.captures(line) {
+        Some(caps) => LogRecord {
+            instance_id: instance_id.to_string(),
+            timestamp: None,
+            time: Some(caps[1].to_string()),
+            level: caps[3].to_string(),
+            logger: None,
+            thread: Some(caps[2].to_string()),
+            message: caps[4].to_string(),
+            throwable: None,
+        },
+        None => LogRecord {
+            instance_id: instance_id.to_string(),
+            timestamp: None,
+            time: None,
+            level: if is_stderr { "ERROR" } else { "INFO" }.to_string(),
+            logger: None,
+            thread: None,
+            message: line.to_string(),
+            throwable: None,
+        },
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+fn sanitize(instance_id: &str) -> String {
+    instance_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// Keep room for one more log of this instance
+fn rotate(logs_dir: &Path, prefix: &str) {
+    let Ok(entries) = fs::read_dir(logs_dir) else {
+        return;
+    };
+    let mut logs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with(prefix) && n.ends_with(".log")))
+        .collect();
+    // Timestamps in the names sort chronologically
+    logs.sort();
+    while logs.len() >= MAX_LOG_FILES {
+        let oldest = logs.remove(0);
+        let _ = fs::remove_file(&oldest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_lines_round_trip() {
+        let line = "[12:34:56] [Render thread/WARN]: Missing sound for event";
+        let record = plain_record("inst", line, false);
+        assert_eq!(record.level, "WARN");
+        assert_eq!(record.thread.as_deref(), Some("Render thread"));
+        assert_eq!(record.message, "Missing sound for event");
+        assert_eq!(record.to_line(), line);
+    }
+
+    #[test]
+    fn unstructured_lines_keep_stream_level() {
+        let record = plain_record("inst", "Exception in thread \"main\"", true);
+        assert_eq!(record.level, "ERROR");
+        assert_eq!(record.to_line(), "Exception in thread \"main\"");
+    }
+
+    #[test]
+    fn parses_log4j_events() {
+        let xml = r#"<log4j:Event logger="net.minecraft.client.Minecraft" timestamp="1700000000000" level="ERROR" thread="Render thread">
+  <log4j:Message><![CDATA[Failed to load texture]]></log4j:Message>
+</log4j:Event>"#;
+        let record = parse_log4j_event(xml, "inst").unwrap();
+        assert_eq!(record.level, "ERROR");
+        assert_eq!(record.thread.as_deref(), Some("Render thread"));
+        assert_eq!(record.logger.as_deref(), Some("net.minecraft.client.Minecraft"));
+        assert_eq!(record.timestamp, Some(1_700_000_000_000));
+        assert_eq!(record.message, "Failed to load texture");
+        assert!(record.to_line().ends_with("] [Render thread/ERROR]: Failed to load texture"));
+    }
+}
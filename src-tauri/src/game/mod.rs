@@ -1,18 +1,29 @@
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::path::{Path, PathBuf};
 use std::fs;
 
+pub mod assets;
+pub mod crash;
 pub mod downloader;
+pub mod installer;
+pub mod jvm_args;
 pub mod launcher;
+pub mod logging;
+pub mod logs;
+pub mod rules;
 pub mod scanner;
 pub mod skins;
+pub mod supervisor;
+
+pub use rules::{OsRule, Rule, RuleContext};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Manifest {
     pub id: String,
     #[serde(rename = "minecraftVersion")]
     pub minecraft_version: Option<String>,
-    #[serde(rename = "mainClass")]
+    #[serde(rename = "mainClass", default)]
     pub main_class: String,
     #[serde(rename = "minecraftArguments")]
     pub minecraft_arguments: Option<String>,
@@ -24,16 +35,45 @@ pub struct Manifest {
     pub inherits_from: Option<String>,
     pub jar: Option<String>,
     pub assets: Option<String>,
-    // Fields from original Manifest that are not in the new structure, but might be needed
-    // If these are truly removed, then the code using them will need to be updated.
-    // For now, assuming the user wants to replace the *definition* of Manifest with the new one.
-    // The original Manifest had:
-    // pub forge_version: Option<String>,
-    // pub version_type: manifest::VersionType,
-    // pub sha256: String,
-    // pub size: u64,
-    // pub release_time: String,
-    // pub asset_index: manifest::AssetIndex,
+    #[serde(rename = "type")]
+    pub version_type: Option<String>,
+    pub downloads: Option<VersionDownloads>,
+    #[serde(rename = "assetIndex")]
+    pub asset_index: Option<AssetIndex>,
+    #[serde(rename = "javaVersion")]
+    pub java_version: Option<JavaVersion>,
+    #[serde(default)]
+    pub logging: Option<Logging>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Logging {
+    pub client: Option<LoggingConfig>,
+}
+
+/// `logging.client`: a log4j2 config file and the JVM argument that points at it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// e.g. "-Dlog4j.configurationFile=${path}"
+    pub argument: String,
+    pub file: LoggingFile,
+    #[serde(rename = "type")]
+    pub config_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingFile {
+    pub id: String,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JavaVersion {
+    pub component: Option<String>,
+    #[serde(rename = "majorVersion")]
+    pub major_version: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,7 +86,55 @@ pub struct Arguments {
 #[serde(untagged)]
 pub enum ArgumentValue {
     String(String),
-    Object(serde_json::Value),
+    Object(ConditionalArgument),
+}
+
+/// `{ "rules": [...], "value": "..." | ["...", ...] }` entry in `arguments.jvm/game`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConditionalArgument {
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+    pub value: ArgumentValues,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ArgumentValues {
+    Single(String),
+    Many(Vec<String>),
+}
+
+impl ArgumentValue {
+    /// Expand a list of manifest arguments, dropping conditional ones whose rules don't apply
+    pub fn resolve_all(args: &[ArgumentValue], ctx: &RuleContext) -> Vec<String> {
+        let mut resolved = Vec::new();
+        for arg in args {
+            match arg {
+                ArgumentValue::String(s) => resolved.push(s.clone()),
+                ArgumentValue::Object(cond) => {
+                    if !rules::rules_allow(Some(&cond.rules), ctx) {
+                        continue;
+                    }
+                    match &cond.value {
+                        ArgumentValues::Single(s) => resolved.push(s.clone()),
+                        ArgumentValues::Many(values) => resolved.extend(values.iter().cloned()),
+                    }
+                }
+            }
+        }
+        resolved
+    }
+
+    /// Whether any conditional argument depends on the given launcher feature
+    pub fn uses_feature(args: &[ArgumentValue], feature: &str) -> bool {
+        args.iter().any(|arg| match arg {
+            ArgumentValue::Object(cond) => cond
+                .rules
+                .iter()
+                .any(|rule| rule.features.as_ref().is_some_and(|f| f.contains_key(feature))),
+            ArgumentValue::String(_) => false,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,82 +161,11 @@ pub struct Artifact {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Rule {
-    pub action: String,
-    pub os: Option<OsRule>,
+pub struct VersionDownloads {
+    pub client: Option<Artifact>,
+    pub server: Option<Artifact>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OsRule {
-    pub name: Option<String>,
-}
-
-// The original Manifest also had DownloadUrls and AssetIndex, which are now part of the new Manifest or removed.
-// Re-adding DownloadUrls and AssetIndex based on the original structure, as they are still referenced in the code.
-// This assumes the user's provided Manifest is an *addition* to the existing structure,
-// or that the existing code will be adapted to the new Manifest.
-// Given the instruction "Replace the Manifest struct", I will replace it.
-// However, the existing code relies on `manifest.download_urls.minecraft`, `manifest.sha256`, `manifest.asset_index`, etc.
-// The new Manifest struct does not have these directly.
-// This implies a larger refactoring is needed, but my task is to make the change faithfully.
-// The user provided a new Manifest struct, which is different from the old one.
-// I will replace the old `pub mod manifest;` and `pub use manifest::Manifest;` with the new struct definitions.
-// This will cause compilation errors in the rest of the file, which is expected as per "make the change faithfully".
-
-// Based on the original file, the `Manifest` struct was defined in `manifest.rs`.
-// The instruction provides a new `Manifest` struct definition.
-// The instruction also includes a snippet of the `install_version` function, which should not be part of the struct definitions.
-// I will remove `pub mod manifest;` and `pub use manifest::Manifest;` and insert the new struct definitions.
-// I will also add the `use` statements from the provided new code.
-
-// Original `manifest` module content (inferred from usage):
-// pub struct Manifest {
-//     pub id: String,
-//     pub minecraft_version: String,
-//     pub forge_version: Option<String>,
-//     pub version_type: VersionType,
-//     pub download_urls: DownloadUrls,
-//     pub sha256: String,
-//     pub size: u64,
-//     pub release_time: String,
-//     pub main_class: String,
-//     pub asset_index: AssetIndex,
-//     pub arguments: Arguments,
-// }
-//
-// pub struct DownloadUrls {
-//     pub minecraft: String,
-//     pub forge: Option<String>,
-//     pub libraries: Vec<String>,
-// }
-//
-// pub struct AssetIndex {
-//     pub id: String,
-//     pub sha1: String,
-//     pub size: u64,
-//     pub url: String,
-//     pub total_size: u64,
-// }
-//
-// pub enum VersionType { ... }
-//
-// pub struct Arguments { ... }
-//
-// pub struct AssetIndexContent { ... }
-
-// The user's instruction is to *replace* the Manifest struct.
-// This means the old `Manifest` struct and its associated types (DownloadUrls, AssetIndex, VersionType, Arguments, AssetIndexContent)
-// that were implicitly part of the `manifest` module are now gone, and only the new ones are present.
-// The provided code snippet for replacement does not include DownloadUrls, AssetIndex, VersionType, etc.
-// This will break the existing `GameManager` implementation. I must make the change faithfully.
-
-// The `puse` typo needs to be corrected to `use`.
-// The `manifest::` prefix for `VersionType` and `AssetIndex` will no longer be valid if they are not defined in a `manifest` module.
-// Since I'm replacing the `pub mod manifest;` and `pub use manifest::Manifest;` lines,
-// the new structs will be directly in the current module.
-
-// Corrected `use` statements and new struct definitions:
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadUrls {
     pub minecraft: String,
@@ -162,6 +179,7 @@ pub struct AssetIndex {
     pub sha1: String,
     pub size: u64,
     pub url: String,
+    #[serde(rename = "totalSize", default)]
     pub total_size: u64,
 }
 
@@ -176,6 +194,12 @@ pub enum VersionType {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssetIndexContent {
     pub objects: std::collections::HashMap<String, AssetObject>,
+    /// Pre-1.7 indexes: objects are also laid out by name under assets/virtual/<id>
+    #[serde(rename = "virtual", default)]
+    pub is_virtual: bool,
+    /// Very old versions (pre-1.6) read assets from <game_dir>/resources
+    #[serde(default)]
+    pub map_to_resources: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -184,64 +208,138 @@ pub struct AssetObject {
     pub size: u64,
 }
 
-pub struct GameManager {
-    pub base_dir: PathBuf,
+impl Library {
+    pub fn version(&self) -> &str {
+        self.name.split(':').nth(2).unwrap_or("")
+    }
+
+    /// "group:artifact" plus the classifier when present, i.e. the coordinate without its version
+    pub fn key(&self) -> String {
+        let parts: Vec<&str> = self.name.split(':').collect();
+        match parts.len() {
+            0..=2 => self.name.clone(),
+            3 => format!("{}:{}", parts[0], parts[1]),
+            _ => format!("{}:{}:{}", parts[0], parts[1], parts[3..].join(":")),
+        }
+    }
 }
 
-impl GameManager {
-    pub fn new(base_dir: PathBuf) -> Self {
-        Self { base_dir }
+/// Merge an `inheritsFrom` child (Fabric, Quilt, Forge, NeoForge profile) onto its already
+/// resolved parent the way the official launcher does:
+/// - scalar fields: the child wins when it sets them
+/// - `arguments.jvm/game`: parent first, then child
+/// - `libraries`: child first; parent entries with the same group:artifact(:classifier) are dropped
+/// - `jar`: the parent's client jar unless the child ships its own
+pub fn merge_manifests(child: Manifest, parent: Manifest) -> Manifest {
+    let child_has_client = child.downloads.as_ref().is_some_and(|d| d.client.is_some());
+    let jar = child.jar.or(if child_has_client {
+        None
+    } else {
+        Some(parent.jar.clone().unwrap_or_else(|| parent.id.clone()))
+    });
+
+    let arguments = match (parent.arguments, child.arguments) {
+        (Some(p), Some(c)) => Some(Arguments {
+            game: concat_args(p.game, c.game),
+            jvm: concat_args(p.jvm, c.jvm),
+        }),
+        (p, c) => c.or(p),
+    };
+
+    let child_libs = child.libraries.unwrap_or_default();
+    let overridden: std::collections::HashSet<String> = child_libs.iter().map(Library::key).collect();
+    let mut libraries = child_libs;
+    libraries.extend(
+        parent
+            .libraries
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|lib| !overridden.contains(&lib.key())),
+    );
+
+    Manifest {
+        id: child.id,
+        minecraft_version: child.minecraft_version.or(parent.minecraft_version),
+        main_class: if child.main_class.is_empty() { parent.main_class } else { child.main_class },
+        minecraft_arguments: child.minecraft_arguments.or(parent.minecraft_arguments),
+        arguments,
+        download_urls: child.download_urls.or(parent.download_urls),
+        libraries: Some(libraries),
+        inherits_from: child.inherits_from,
+        jar,
+        assets: child.assets.or(parent.assets),
+        version_type: child.version_type.or(parent.version_type),
+        downloads: child.downloads.or(parent.downloads),
+        asset_index: child.asset_index.or(parent.asset_index),
+        java_version: child.java_version.or(parent.java_version),
+        logging: child.logging.or(parent.logging),
     }
-    
-    /*
-    // TEMPORARILY DISABLED - Uses old manifest format
-    pub async fn install_version(&self, manifest_url: &str, app_handle: &tauri::AppHandle) -> Result<Manifest, String> {
-        let client = reqwest::Client::new();
-        
-        // 1. Fetch Manifest
-        let res = client.get(manifest_url).send().await.map_err(|e| e.to_string())?;
-        if !res.status().is_success() {
-             return Err(format!("Failed to fetch manifest: {}", res.status()));
-        }
-        let manifest: Manifest = res.json().await.map_err(|e| e.to_string())?;
-        
-        let version_dir = self.base_dir.join("versions").join(&manifest.id);
-        let lib_dir = self.base_dir.join("libraries");
-        
-        // 2. Download Minecraft Client
-        let client_jar = version_dir.join(format!("{}.jar", manifest.id));
-        downloader::download_file(&client, &manifest.download_urls.minecraft, &client_jar, Some(&manifest.sha256), app_handle).await?;
-        
-        // 3. Download Libraries (Parallelize ideally, but sequential for now)
-        for lib_url in &manifest.download_urls.libraries {
-            // Simple filename extraction
-            let filename = lib_url.split('/').last().unwrap_or("library.jar");
-            let lib_path = lib_dir.join(filename); // In reality, should follow maven path structure
-            downloader::download_file(&client, lib_url, &lib_path, None, app_handle).await?;
+}
+
+fn concat_args(parent: Option<Vec<ArgumentValue>>, child: Option<Vec<ArgumentValue>>) -> Option<Vec<ArgumentValue>> {
+    match (parent, child) {
+        (Some(mut p), Some(c)) => {
+            p.extend(c);
+            Some(p)
         }
-        
-        // 4. Download Forge if needed
-        if let Some(forge_url) = &manifest.download_urls.forge {
-            let forge_installer = version_dir.join("forge-installer.jar");
-            downloader::download_file(&client, forge_url, &forge_installer, None, app_handle).await?;
+        (p, c) => c.or(p),
+    }
+}
+
+/// Classpath libraries after conflict resolution
+#[derive(Debug, Clone, Default)]
+pub struct LibraryResolution {
+    pub paths: Vec<PathBuf>,
+    /// Human readable "<dropped> (keeping <kept>)" entries for the launch log
+    pub dropped: Vec<String>,
+}
+
+/// Compare dotted/dashed version strings numerically where possible ("9.2" < "9.10")
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> Vec<String> {
+        v.split(['.', '-', '_', '+']).map(|s| s.to_string()).collect()
+    };
+    let (pa, pb) = (split(a), split(b));
+    for (x, y) in pa.iter().zip(pb.iter()) {
+        let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(nx), Ok(ny)) => nx.cmp(&ny),
+            _ => x.cmp(y),
+        };
+        if ord != Ordering::Equal {
+            return ord;
         }
-        
-        // 5. Download Assets [NEW]
-        self.download_assets(&client, &manifest, app_handle).await?;
+    }
+    pa.len().cmp(&pb.len())
+}
 
-        // 6. Extract Natives [NEW]
-        self.extract_natives(&manifest)?;
+pub struct GameManager {
+    pub base_dir: PathBuf,
+    /// Where asset objects are fetched from, `<base>/<hash prefix>/<hash>`
+    pub assets_base_url: String,
+}
 
-        // 7. Save Manifest
-        let manifest_path = version_dir.join("manifest.json");
-        let manifest_json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
-        tokio::fs::write(manifest_path, manifest_json).await.map_err(|e| e.to_string())?;
-        
-        Ok(manifest)
+impl GameManager {
+    pub fn new(base_dir: PathBuf) -> Self {
+        Self { base_dir, assets_base_url: assets::ASSETS_BASE_URL.to_string() }
     }
-    */
 
+    /// Fetch asset objects from another host, e.g. a mirror or a local stand-in when testing
+    pub fn with_assets_base_url(mut self, url: impl Into<String>) -> Self {
+        self.assets_base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+    
     pub async fn load_manifest(&self, id: &str) -> Result<Manifest, String> {
+        self.load_manifest_chain(id, &mut Vec::new()).await
+    }
+
+    async fn load_manifest_chain(&self, id: &str, chain: &mut Vec<String>) -> Result<Manifest, String> {
+        if chain.iter().any(|c| c == id) {
+            chain.push(id.to_string());
+            return Err(format!("Circular inheritsFrom chain: {}", chain.join(" -> ")));
+        }
+        chain.push(id.to_string());
+
         let custom_path = self.base_dir.join("versions").join(id).join("manifest.json");
         let official_path = self.base_dir.join("versions").join(id).join(format!("{}.json", id));
         
@@ -254,30 +352,17 @@ impl GameManager {
         };
         
         let content = tokio::fs::read_to_string(&path).await.map_err(|e| e.to_string())?;
-        let mut manifest: Manifest = serde_json::from_str(&content).map_err(|e| format!("Failed to parse manifest: {}", e))?;
+        let manifest: Manifest = serde_json::from_str(&content).map_err(|e| format!("Failed to parse manifest: {}", e))?;
         
         // Handle inheritance (for Forge/Fabric)
-        if let Some(parent_id) = &manifest.inherits_from.clone() {
-            println!("DEBUG: Loading parent manifest: {}", parent_id);
-            let parent = Box::pin(self.load_manifest(parent_id)).await?;
-            
-            // Merge parent into current manifest
-            if manifest.minecraft_arguments.is_none() {
-                manifest.minecraft_arguments = parent.minecraft_arguments;
-            }
-            if manifest.assets.is_none() {
-                manifest.assets = parent.assets;
+        match manifest.inherits_from.clone() {
+            Some(parent_id) => {
+                println!("DEBUG: Loading parent manifest: {}", parent_id);
+                let parent = Box::pin(self.load_manifest_chain(&parent_id, chain)).await?;
+                Ok(merge_manifests(manifest, parent))
             }
-            
-            // Merge libraries: parent first, then child (child can override)
-            let mut all_libs = parent.libraries.unwrap_or_default();
-            if let Some(mut child_libs) = manifest.libraries {
-                all_libs.append(&mut child_libs);
-            }
-            manifest.libraries = Some(all_libs);
+            None => Ok(manifest),
         }
-        
-        Ok(manifest)
     }
 
     /// Convert Maven coordinate to file path
@@ -299,105 +384,72 @@ impl GameManager {
 
     /// Get all library paths for a manifest (including inherited ones)
     pub fn get_library_paths(&self, manifest: &Manifest) -> Vec<PathBuf> {
-        let mut paths = Vec::new();
+        self.resolve_libraries(manifest).paths
+    }
+
+    /// Resolve the classpath libraries, keeping a single version per group:artifact(:classifier).
+    ///
+    /// `merge_manifests` already lets a child override its parent's entries; duplicates left
+    /// within a single list resolve to the newest version.
+    pub fn resolve_libraries(&self, manifest: &Manifest) -> LibraryResolution {
         let lib_dir = self.base_dir.join("libraries");
-        
-        if let Some(libraries) = &manifest.libraries {
-            for lib in libraries {
-                // Check if library should be included based on rules
-                if !self.should_include_library(lib) {
-                    continue;
-                }
-                
-                // Try to get path from downloads first (official Minecraft format)
-                if let Some(downloads) = &lib.downloads {
-                    if let Some(artifact) = &downloads.artifact {
-                        if let Some(path_str) = &artifact.path {
-                            paths.push(lib_dir.join(path_str));
-                            continue;
-                        }
-                    }
-                }
-                
-                // Fallback to Maven coordinate parsing (Forge format)
-                let maven_path = Self::maven_to_path(&lib.name);
-                paths.push(lib_dir.join(&maven_path));
+        let mut kept: Vec<(String, &Library)> = Vec::new();
+        let mut dropped = Vec::new();
+
+        for lib in manifest.libraries.iter().flatten() {
+            // Check if library should be included based on rules
+            if !self.should_include_library(lib) {
+                continue;
             }
-        }
-        
-        paths
-    }
 
-    fn should_include_library(&self, lib: &Library) -> bool {
-        if let Some(rules) = &lib.rules {
-            for rule in rules {
-                let matches = if let Some(os) = &rule.os {
-                    if let Some(os_name) = &os.name {
-                        // Check if current OS matches
-                        cfg!(target_os = "windows") && os_name == "windows" ||
-                        cfg!(target_os = "linux") && os_name == "linux" ||
-                        cfg!(target_os = "macos") && os_name == "osx"
+            let key = lib.key();
+            match kept.iter_mut().find(|(k, _)| *k == key) {
+                Some(entry) => {
+                    let current = entry.1;
+                    if compare_versions(lib.version(), current.version()) == Ordering::Greater {
+                        dropped.push(format!("{} (keeping {})", current.name, lib.name));
+                        entry.1 = lib;
                     } else {
-                        true
+                        dropped.push(format!("{} (keeping {})", lib.name, current.name));
                     }
-                } else {
-                    true
-                };
-                
-                if rule.action == "allow" && !matches {
-                    return false;
-                }
-                if rule.action == "disallow" && matches {
-                    return false;
                 }
+                None => kept.push((key, lib)),
             }
         }
-        true
-    }
 
-    /*
-    // TEMPORARILY DISABLED - Uses old manifest format
-    async fn download_assets(&self, client: &reqwest::Client, manifest: &Manifest, app_handle: &tauri::AppHandle) -> Result<(), String> {
-        let assets_dir = self.base_dir.join("assets");
-        let objects_dir = assets_dir.join("objects");
-        let indexes_dir = assets_dir.join("indexes");
-        
-        std::fs::create_dir_all(&objects_dir).map_err(|e| e.to_string())?;
-        std::fs::create_dir_all(&indexes_dir).map_err(|e| e.to_string())?;
+        let paths = kept
+            .into_iter()
+            .map(|(_, lib)| {
+                // Try to get path from downloads first (official Minecraft format)
+                let artifact_path = lib
+                    .downloads
+                    .as_ref()
+                    .and_then(|d| d.artifact.as_ref())
+                    .and_then(|a| a.path.as_ref());
+                match artifact_path {
+                    Some(path_str) => lib_dir.join(path_str),
+                    // Fallback to Maven coordinate parsing (Forge format)
+                    None => lib_dir.join(Self::maven_to_path(&lib.name)),
+                }
+            })
+            .collect();
 
-        // Download Index
-        let index_path = indexes_dir.join(format!("{}.json", manifest.asset_index.id));
-        downloader::download_file(client, &manifest.asset_index.url, &index_path, Some(&manifest.asset_index.sha1), app_handle).await?;
+        LibraryResolution { paths, dropped }
+    }
 
-        // Parse Index
-        let content = tokio::fs::read_to_string(&index_path).await.map_err(|e| e.to_string())?;
-        let index: AssetIndexContent = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    /// Resolve the natives classifier for the current OS (e.g. "natives-windows"),
+    /// expanding the `${arch}` placeholder used by older LWJGL entries
+    pub fn native_classifier(lib: &Library) -> Option<String> {
+        let natives = lib.natives.as_ref()?;
+        let os_key = rules::current_os_name();
+        let arch = if cfg!(target_arch = "x86") { "32" } else { "64" };
+        natives.get(os_key).map(|c| c.replace("${arch}", arch))
+    }
 
-        // Download Objects
-        let mut count = 0;
-        let total = index.objects.len();
-        
-        // Batch download to limit connections? For now, sequential to be safe with limited connections
-        // Or parallel with semaphore. Let's keep it simple sequential but emit only occasionally to avoid spam
-        for (_name, object) in index.objects {
-            let hash = object.hash;
-            let sub_hash = &hash[0..2];
-            let url = format!("https://resources.download.minecraft.net/{}/{}", sub_hash, hash);
-            let path = objects_dir.join(sub_hash).join(&hash);
-            
-            if !path.exists() {
-               downloader::download_file(client, &url, &path, Some(&hash), app_handle).await?;
-            }
-            count += 1;
-            // Emit progress occasionally? downloader emits per file chunk, which is too much for 3000 files.
-            // downloader::download_file emits per chunk. That might be bad for 1000 tiny files.
-            // We should ideally silence downloader for assets or just accept it.
-        }
-        
-        Ok(())
+    pub(crate) fn should_include_library(&self, lib: &Library) -> bool {
+        rules::rules_allow(lib.rules.as_deref(), &RuleContext::default())
     }
 
-    */
     pub fn extract_natives(&self, manifest: &Manifest) -> Result<(), String> {
         let version_dir = self.base_dir.join("versions").join(&manifest.id);
         let natives_dir = version_dir.join("natives");
@@ -413,79 +465,65 @@ impl GameManager {
                 }
 
                 // Check for natives
-                if let Some(natives) = &lib.natives {
-                    let os_key = if cfg!(target_os = "windows") {
-                        "windows"
-                    } else if cfg!(target_os = "macos") {
-                        "osx" // Minecraft uses 'osx' usually
-                    } else {
-                        "linux"
-                    };
-
-                    if let Some(classifier_key) = natives.get(os_key) {
-                        // Resolve placeholder like ${arch}
-                        let arch = if cfg!(target_arch = "x86") { "32" } else { "64" };
-                        let classifier = classifier_key.replace("${arch}", arch);
-
-                        // Find the artifact path
-                        let mut jar_path = None;
-
-                        // Try downloads.classifiers first (official JSON)
-                        if let Some(downloads) = &lib.downloads {
-                            if let Some(classifiers) = &downloads.classifiers {
-                                if let Some(artifact) = classifiers.get(&classifier) {
-                                    if let Some(path) = &artifact.path {
-                                        jar_path = Some(lib_dir.join(path));
-                                    }
+                if let Some(classifier) = Self::native_classifier(lib) {
+                    // Find the artifact path
+                    let mut jar_path = None;
+
+                    // Try downloads.classifiers first (official JSON)
+                    if let Some(downloads) = &lib.downloads {
+                        if let Some(classifiers) = &downloads.classifiers {
+                            if let Some(artifact) = classifiers.get(&classifier) {
+                                if let Some(path) = &artifact.path {
+                                    jar_path = Some(lib_dir.join(path));
                                 }
                             }
                         }
+                    }
 
-                        // Fallback/Legacy: Construct path from maven coordinates if not found in downloads
-                        if jar_path.is_none() {
-                             // Maven path construction with classifier
-                             let parts: Vec<&str> = lib.name.split(':').collect();
-                             if parts.len() >= 3 {
-                                 let group = parts[0].replace('.', "/");
-                                 let artifact = parts[1];
-                                 let version = parts[2];
-                                 let path = format!("{}/{}/{}/{}-{}-{}.jar", group, artifact, version, artifact, version, classifier);
-                                 jar_path = Some(lib_dir.join(path));
-                             }
-                        }
+                    // Fallback/Legacy: Construct path from maven coordinates if not found in downloads
+                    if jar_path.is_none() {
+                         // Maven path construction with classifier
+                         let parts: Vec<&str> = lib.name.split(':').collect();
+                         if parts.len() >= 3 {
+                             let group = parts[0].replace('.', "/");
+                             let artifact = parts[1];
+                             let version = parts[2];
+                             let path = format!("{}/{}/{}/{}-{}-{}.jar", group, artifact, version, artifact, version, classifier);
+                             jar_path = Some(lib_dir.join(path));
+                         }
+                    }
 
-                        if let Some(path) = jar_path {
-                            if path.exists() {
-                                println!("DEBUG: Extracting native: {:?}", path);
-                                if let Ok(file) = std::fs::File::open(&path) {
-                                    if let Ok(mut archive) = zip::ZipArchive::new(file) {
-                                        // Exclusion list
-                                        let excludes = vec!["META-INF", ".git", ".sha1"];
-
-                                        for i in 0..archive.len() {
-                                            if let Ok(mut file) = archive.by_index(i) {
-                                                if let Some(outpath) = file.enclosed_name() {
-                                                    // Check exclusions
-                                                    let path_str = outpath.to_string_lossy();
-                                                    if excludes.iter().any(|e| path_str.contains(e)) { continue; }
-
-                                                    // Extract .dll, .so, .dylib
-                                                    let ext = outpath.extension().and_then(|e| e.to_str()).unwrap_or("");
-                                                    if ext == "dll" || ext == "so" || ext == "dylib" {
-                                                         let out_file_path = natives_dir.join(outpath.file_name().unwrap());
-                                                         // Only extract if not exists or different? For now always extract to ensure correctness
-                                                         if let Ok(mut outfile) = std::fs::File::create(&out_file_path) {
-                                                             let _ = std::io::copy(&mut file, &mut outfile);
-                                                         }
-                                                    }
+                    if let Some(path) = jar_path {
+                        if path.exists() {
+                            println!("DEBUG: Extracting native: {:?}", path);
+                            if let Ok(file) = std::fs::File::open(&path) {
+                                if let Ok(mut archive) = zip::ZipArchive::new(file) {
+                                    // Exclusion list
+                                    let excludes = vec!["META-INF", ".git", ".sha1"];
+
+                                    for i in 0..archive.len() {
+                                        if let Ok(mut file) = archive.by_index(i) {
+                                            if let Some(outpath) = file.enclosed_name() {
+                                                // Check exclusions
+                                                let path_str = outpath.to_string_lossy();
+                                                if excludes.iter().any(|e| path_str.contains(e)) { continue; }
+
+                                                // Extract .dll, .so, .dylib
+                                                let ext = outpath.extension().and_then(|e| e.to_str()).unwrap_or("");
+                                                if ext == "dll" || ext == "so" || ext == "dylib" {
+                                                     let out_file_path = natives_dir.join(outpath.file_name().unwrap());
+                                                     // Only extract if not exists or different? For now always extract to ensure correctness
+                                                     if let Ok(mut outfile) = std::fs::File::create(&out_file_path) {
+                                                         let _ = std::io::copy(&mut file, &mut outfile);
+                                                     }
                                                 }
                                             }
                                         }
                                     }
                                 }
-                            } else {
-                                println!("WARN: Native jar not found at {:?}", path);
                             }
+                        } else {
+                            println!("WARN: Native jar not found at {:?}", path);
                         }
                     }
                 }
@@ -495,3 +533,163 @@ impl GameManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const VANILLA_1_8_9: &str = include_str!("../../../versions/1.8.9/1.8.9.json");
+
+    fn vanilla() -> Manifest {
+        serde_json::from_str(VANILLA_1_8_9).unwrap()
+    }
+
+    fn manifest(value: serde_json::Value) -> Manifest {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn library_names(manifest: &Manifest) -> Vec<&str> {
+        manifest.libraries.iter().flatten().map(|l| l.name.as_str()).collect()
+    }
+
+    fn string_args(args: &Option<Vec<ArgumentValue>>) -> Vec<&str> {
+        args.iter()
+            .flatten()
+            .filter_map(|a| match a {
+                ArgumentValue::String(s) => Some(s.as_str()),
+                ArgumentValue::Object(_) => None,
+            })
+            .collect()
+    }
+
+    /// Forge-style child of the 1.8.9 fixture, overriding guava and adding its own libraries
+    fn forge_child() -> Manifest {
+        manifest(json!({
+            "id": "1.8.9-forge1.8.9-11.15.1.2318-1.8.9",
+            "inheritsFrom": "1.8.9",
+            "mainClass": "net.minecraft.launchwrapper.Launch",
+            "minecraftArguments": "--tweakClass net.minecraftforge.fml.common.launcher.FMLTweaker",
+            "libraries": [
+                { "name": "net.minecraftforge:forge:1.8.9-11.15.1.2318-1.8.9" },
+                { "name": "com.google.guava:guava:21.0" }
+            ]
+        }))
+    }
+
+    #[test]
+    fn child_libraries_override_parent_by_key() {
+        let merged = merge_manifests(forge_child(), vanilla());
+        let names = library_names(&merged);
+
+        assert_eq!(names[0], "net.minecraftforge:forge:1.8.9-11.15.1.2318-1.8.9");
+        assert_eq!(names[1], "com.google.guava:guava:21.0");
+        assert!(!names.contains(&"com.google.guava:guava:17.0"));
+        assert!(names.contains(&"com.mojang:authlib:1.5.21"));
+        assert_eq!(names.len(), vanilla().libraries.unwrap().len() + 1);
+    }
+
+    #[test]
+    fn library_key_keeps_classifier() {
+        let lib = |name: &str| Library { name: name.to_string(), url: None, downloads: None, natives: None, rules: None };
+        assert_eq!(lib("org.lwjgl:lwjgl:3.3.1").key(), "org.lwjgl:lwjgl");
+        assert_eq!(lib("org.lwjgl:lwjgl:3.3.1:natives-linux").key(), "org.lwjgl:lwjgl:natives-linux");
+    }
+
+    #[test]
+    fn arguments_concatenate_parent_first() {
+        let parent = manifest(json!({
+            "id": "1.20.1",
+            "mainClass": "net.minecraft.client.main.Main",
+            "arguments": { "game": ["--username", "${auth_player_name}"], "jvm": ["-cp", "${classpath}"] }
+        }));
+        let child = manifest(json!({
+            "id": "fabric-loader-0.15.0-1.20.1",
+            "inheritsFrom": "1.20.1",
+            "arguments": { "game": ["--fabric"], "jvm": ["-DFabricMcEmu= net.minecraft.client.main.Main "] }
+        }));
+
+        let merged = merge_manifests(child, parent);
+        let arguments = merged.arguments.unwrap();
+        assert_eq!(string_args(&arguments.game), ["--username", "${auth_player_name}", "--fabric"]);
+        assert_eq!(string_args(&arguments.jvm), ["-cp", "${classpath}", "-DFabricMcEmu= net.minecraft.client.main.Main "]);
+    }
+
+    #[test]
+    fn child_scalars_take_precedence() {
+        let merged = merge_manifests(forge_child(), vanilla());
+        assert_eq!(merged.id, "1.8.9-forge1.8.9-11.15.1.2318-1.8.9");
+        assert_eq!(merged.main_class, "net.minecraft.launchwrapper.Launch");
+        assert_eq!(merged.jar.as_deref(), Some("1.8.9"));
+        assert_eq!(merged.java_version.as_ref().map(|j| j.major_version), Some(8));
+        assert_eq!(merged.assets.as_deref(), Some("1.8"));
+
+        let child = manifest(json!({
+            "id": "custom",
+            "inheritsFrom": "1.8.9",
+            "jar": "custom-client",
+            "javaVersion": { "component": "java-runtime-gamma", "majorVersion": 17 }
+        }));
+        let merged = merge_manifests(child, vanilla());
+        assert_eq!(merged.main_class, "net.minecraft.client.main.Main");
+        assert_eq!(merged.jar.as_deref(), Some("custom-client"));
+        assert_eq!(merged.java_version.map(|j| j.major_version), Some(17));
+    }
+
+    /// Version directory under the system temp dir, removed when dropped
+    struct TempVersions(PathBuf);
+
+    impl TempVersions {
+        fn new() -> Self {
+            Self(std::env::temp_dir().join(format!("jugelauncher-test-{}", uuid::Uuid::new_v4())))
+        }
+
+        fn write(&self, id: &str, content: &str) {
+            let dir = self.0.join("versions").join(id);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(format!("{}.json", id)), content).unwrap();
+        }
+    }
+
+    impl Drop for TempVersions {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn loads_multi_level_chain() {
+        let dir = TempVersions::new();
+        dir.write("1.8.9", VANILLA_1_8_9);
+        dir.write("forge", &json!({
+            "id": "forge",
+            "inheritsFrom": "1.8.9",
+            "mainClass": "net.minecraft.launchwrapper.Launch",
+            "libraries": [{ "name": "net.minecraftforge:forge:1.8.9-11.15.1.2318-1.8.9" }]
+        }).to_string());
+        dir.write("modpack", &json!({
+            "id": "modpack",
+            "inheritsFrom": "forge",
+            "libraries": [{ "name": "net.minecraftforge:forge:1.8.9-11.15.1.2847-1.8.9" }]
+        }).to_string());
+
+        let merged = GameManager::new(dir.0.clone()).load_manifest("modpack").await.unwrap();
+        let names = library_names(&merged);
+        assert_eq!(merged.id, "modpack");
+        assert_eq!(merged.main_class, "net.minecraft.launchwrapper.Launch");
+        assert_eq!(merged.jar.as_deref(), Some("1.8.9"));
+        assert_eq!(names[0], "net.minecraftforge:forge:1.8.9-11.15.1.2847-1.8.9");
+        assert!(!names.contains(&"net.minecraftforge:forge:1.8.9-11.15.1.2318-1.8.9"));
+        assert!(names.contains(&"com.google.guava:guava:17.0"));
+    }
+
+    #[tokio::test]
+    async fn detects_circular_inheritance() {
+        let dir = TempVersions::new();
+        dir.write("a", &json!({ "id": "a", "inheritsFrom": "b" }).to_string());
+        dir.write("b", &json!({ "id": "b", "inheritsFrom": "a" }).to_string());
+
+        let err = GameManager::new(dir.0.clone()).load_manifest("a").await.unwrap_err();
+        assert_eq!(err, "Circular inheritsFrom chain: a -> b -> a");
+    }
+}
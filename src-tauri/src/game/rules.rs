@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use regex::Regex;
+use crate::process::hide_console;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    pub action: String,
+    pub os: Option<OsRule>,
+    pub features: Option<HashMap<String, bool>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OsRule {
+    pub name: Option<String>,
+    pub arch: Option<String>,
+    /// Regex matched against the OS version, e.g. "^10\\." for Windows 10
+    pub version: Option<String>,
+}
+
+/// Launcher features that conditional arguments can depend on
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Features {
+    pub is_demo_user: bool,
+    pub has_custom_resolution: bool,
+    pub has_quick_plays_support: bool,
+    pub is_quick_play_singleplayer: bool,
+    pub is_quick_play_multiplayer: bool,
+    pub is_quick_play_realms: bool,
+}
+
+impl Features {
+    pub fn is_enabled(&self, name: &str) -> bool {
+        match name {
+            "is_demo_user" => self.is_demo_user,
+            "has_custom_resolution" => self.has_custom_resolution,
+            "has_quick_plays_support" => self.has_quick_plays_support,
+            "is_quick_play_singleplayer" => self.is_quick_play_singleplayer,
+            "is_quick_play_multiplayer" => self.is_quick_play_multiplayer,
+            "is_quick_play_realms" => self.is_quick_play_realms,
+            _ => false,
+        }
+    }
+}
+
+/// Everything a rule can be evaluated against
+#[derive(Debug, Clone)]
+pub struct RuleContext {
+    pub os_name: String,
+    pub os_arch: String,
+    pub os_version: String,
+    pub features: Features,
+}
+
+impl RuleContext {
+    /// Context for the machine we are running on
+    pub fn current(features: Features) -> Self {
+        Self {
+            os_name: current_os_name().to_string(),
+            os_arch: current_os_arch().to_string(),
+            os_version: current_os_version().to_string(),
+            features,
+        }
+    }
+}
+
+impl Default for RuleContext {
+    fn default() -> Self {
+        Self::current(Features::default())
+    }
+}
+
+/// OS name as used in Mojang metadata
+pub fn current_os_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "osx"
+    } else {
+        "linux"
+    }
+}
+
+/// Architecture as used in Mojang metadata ("x86" means 32-bit)
+pub fn current_os_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86" => "x86",
+        "aarch64" => "arm64",
+        "arm" => "arm32",
+        other => other,
+    }
+}
+
+/// OS version string, e.g. "10.0.19045" on Windows or "14.2.1" on macOS
+pub fn current_os_version() -> &'static str {
+    static VERSION: OnceLock<String> = OnceLock::new();
+    VERSION.get_or_init(|| {
+        let output = if cfg!(target_os = "windows") {
+            let mut ver = Command::new("cmd");
+            ver.args(["/C", "ver"]);
+            hide_console(&mut ver);
+            ver.output()
+        } else if cfg!(target_os = "macos") {
+            Command::new("sw_vers").arg("-productVersion").output()
+        } else {
+            Command::new("uname").arg("-r").output()
+        };
+
+        let text = output.map(|o| String::from_utf8_lossy(&o.stdout).to_string()).unwrap_or_default();
+        // "Microsoft Windows [Version 10.0.19045.3803]" -> "10.0.19045.3803"
+        let text = text.trim();
+        match (text.find("Version "), text.rfind(']')) {
+            (Some(start), Some(end)) if end > start => text[start + 8..end].to_string(),
+            _ => text.to_string(),
+        }
+    })
+}
+
+/// `os.version` patterns compiled once; invalid ones are cached as `None` and never match
+fn version_regex(pattern: &str) -> Option<Regex> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Option<Regex>>>> = OnceLock::new();
+    let mut cache = CACHE.get_or_init(Default::default).lock().unwrap();
+    cache
+        .entry(pattern.to_string())
+        .or_insert_with(|| match Regex::new(pattern) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                println!("WARN: Invalid os.version rule {:?}: {}", pattern, e);
+                None
+            }
+        })
+        .clone()
+}
+
+impl Rule {
+    /// Whether this rule's conditions apply in the given context
+    pub fn matches(&self, ctx: &RuleContext) -> bool {
+        if let Some(os) = &self.os {
+            if let Some(name) = &os.name {
+                if name != &ctx.os_name {
+                    return false;
+                }
+            }
+            if let Some(arch) = &os.arch {
+                if arch != &ctx.os_arch {
+                    return false;
+                }
+            }
+            if let Some(version) = &os.version {
+                if !version_regex(version).is_some_and(|re| re.is_match(&ctx.os_version)) {
+                    return false;
+                }
+            }
+        }
+
+        if let Some(features) = &self.features {
+            for (name, expected) in features {
+                if ctx.features.is_enabled(name) != *expected {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Mojang rule semantics: no rules means allowed, otherwise the last matching rule decides
+/// and nothing matching means disallowed
+pub fn rules_allow(rules: Option<&[Rule]>, ctx: &RuleContext) -> bool {
+    let rules = match rules {
+        Some(rules) if !rules.is_empty() => rules,
+        _ => return true,
+    };
+
+    let mut allowed = false;
+    for rule in rules {
+        if rule.matches(ctx) {
+            allowed = rule.action == "allow";
+        }
+    }
+    allowed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parse(value: serde_json::Value) -> Vec<Rule> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn ctx(os_name: &str, os_arch: &str, os_version: &str) -> RuleContext {
+        RuleContext {
+            os_name: os_name.to_string(),
+            os_arch: os_arch.to_string(),
+            os_version: os_version.to_string(),
+            features: Features::default(),
+        }
+    }
+
+    #[test]
+    fn no_rules_allow() {
+        assert!(rules_allow(None, &ctx("linux", "x86_64", "6.1")));
+        assert!(rules_allow(Some(&[]), &ctx("linux", "x86_64", "6.1")));
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        // Every OS except macOS, as on old LWJGL entries
+        let rules = parse(json!([{ "action": "allow" }, { "action": "disallow", "os": { "name": "osx" } }]));
+        assert!(rules_allow(Some(&rules), &ctx("windows", "x86_64", "10.0")));
+        assert!(!rules_allow(Some(&rules), &ctx("osx", "arm64", "14.2")));
+    }
+
+    #[test]
+    fn nothing_matching_disallows() {
+        let rules = parse(json!([{ "action": "allow", "os": { "name": "osx" } }]));
+        assert!(!rules_allow(Some(&rules), &ctx("linux", "x86_64", "6.1")));
+        assert!(rules_allow(Some(&rules), &ctx("osx", "x86_64", "14.2")));
+    }
+
+    #[test]
+    fn matches_arch() {
+        let rules = parse(json!([{ "action": "allow", "os": { "arch": "x86" } }]));
+        assert!(rules_allow(Some(&rules), &ctx("windows", "x86", "10.0")));
+        assert!(!rules_allow(Some(&rules), &ctx("windows", "x86_64", "10.0")));
+    }
+
+    #[test]
+    fn matches_version_regex() {
+        let rules = parse(json!([{ "action": "allow", "os": { "name": "windows", "version": "^10\\." } }]));
+        assert!(rules_allow(Some(&rules), &ctx("windows", "x86_64", "10.0.19045.3803")));
+        assert!(!rules_allow(Some(&rules), &ctx("windows", "x86_64", "6.1.7601")));
+
+        let invalid = parse(json!([{ "action": "allow", "os": { "version": "^(10" } }]));
+        assert!(!rules_allow(Some(&invalid), &ctx("windows", "x86_64", "10.0")));
+    }
+
+    #[test]
+    fn matches_features() {
+        let rules = parse(json!([{ "action": "allow", "features": { "has_custom_resolution": true } }]));
+        let mut with_resolution = ctx("linux", "x86_64", "6.1");
+        assert!(!rules_allow(Some(&rules), &with_resolution));
+        with_resolution.features.has_custom_resolution = true;
+        assert!(rules_allow(Some(&rules), &with_resolution));
+
+        let demo = parse(json!([{ "action": "allow", "features": { "is_demo_user": false } }]));
+        assert!(rules_allow(Some(&demo), &ctx("linux", "x86_64", "6.1")));
+    }
+}
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::process::Child;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+/// How often exit status is polled; `Child::wait` would hold the lock `kill` needs
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A game process started by the launcher, payload of `game_started`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningInstance {
+    /// Profile id, or the version id when launched without a profile
+    pub instance_id: String,
+    pub name: String,
+    pub version_id: String,
+    pub pid: u32,
+    pub started_at: i64,
+}
+
+/// Payload of `game_exited`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameExit {
+    pub instance_id: String,
+    pub version_id: String,
+    pub pid: u32,
+    /// `None` when the process was ended by a signal
+    pub exit_code: Option<i32>,
+    pub runtime_secs: u64,
+    pub killed: bool,
+}
+
+struct Tracked {
+    info: RunningInstance,
+    child: Arc<Mutex<Child>>,
+    killed: Arc<Mutex<bool>>,
+}
+
+/// Keeps every running game process so it can be listed, killed and reported on exit
+#[derive(Clone, Default)]
+pub struct GameSupervisor {
+    running: Arc<Mutex<HashMap<String, Tracked>>>,
+    /// Instances between the launch request and the spawn
+    starting: Arc<Mutex<HashSet<String>>>,
+}
+
+/// Claim on an instance while it is being prepared; released on drop if nothing was spawned
+pub struct LaunchSlot {
+    supervisor: GameSupervisor,
+    instance_id: String,
+}
+
+impl GameSupervisor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve an instance for launching, failing if it is already running or starting
+    pub fn begin_launch(&self, instance_id: &str) -> Result<LaunchSlot, String> {
+        if self.running.lock().unwrap().contains_key(instance_id) {
+            return Err(format!("{} is already running", instance_id));
+        }
+        if !self.starting.lock().unwrap().insert(instance_id.to_string()) {
+            return Err(format!("{} is already launching", instance_id));
+        }
+        Ok(LaunchSlot { supervisor: self.clone(), instance_id: instance_id.to_string() })
+    }
+
+    pub fn list_running(&self) -> Vec<RunningInstance> {
+        let mut instances: Vec<_> = self.running.lock().unwrap().values().map(|t| t.info.clone()).collect();
+        instances.sort_by_key(|i| i.started_at);
+        instances
+    }
+
+    pub fn is_running(&self, instance_id: &str) -> bool {
+        self.running.lock().unwrap().contains_key(instance_id)
+    }
+
+    /// Kill a running instance; `game_exited` follows once the process is gone
+    pub fn kill(&self, instance_id: &str) -> Result<(), String> {
+        let (child, killed) = {
+            let running = self.running.lock().unwrap();
+            let tracked = running.get(instance_id).ok_or_else(|| format!("{} is not running", instance_id))?;
+            (tracked.child.clone(), tracked.killed.clone())
+        };
+        *killed.lock().unwrap() = true;
+        let result = child.lock().unwrap().kill();
+        result.map_err(|e| format!("Failed to kill {}: {}", instance_id, e))
+    }
+}
+
+impl LaunchSlot {
+    /// Hand the spawned process to the supervisor: emits `game_started` now and `game_exited`
+    /// when it ends, after running `on_exit`
+    pub fn track<F>(self, name: &str, version_id: &str, child: Child, app: tauri::AppHandle, on_exit: F) -> RunningInstance
+    where
+        F: FnOnce(&GameExit) + Send + 'static,
+    {
+        let info = RunningInstance {
+            instance_id: self.instance_id.clone(),
+            name: name.to_string(),
+            version_id: version_id.to_string(),
+            pid: child.id(),
+            started_at: chrono::Utc::now().timestamp(),
+        };
+        let child = Arc::new(Mutex::new(child));
+        let killed = Arc::new(Mutex::new(false));
+
+        let supervisor = self.supervisor.clone();
+        supervisor.running.lock().unwrap().insert(
+            info.instance_id.clone(),
+            Tracked { info: info.clone(), child: child.clone(), killed: killed.clone() },
+        );
+        let _ = app.emit("game_started", &info);
+        println!("DEBUG: {} started with PID {}", info.instance_id, info.pid);
+
+        let started = Instant::now();
+        let exited = info.clone();
+        std::thread::spawn(move || {
+            let status = loop {
+                match child.lock().unwrap().try_wait() {
+                    Ok(Some(status)) => break Some(status),
+                    Ok(None) => {}
+                    Err(e) => {
+                        println!("WARN: Lost track of {}: {}", exited.instance_id, e);
+                        break None;
+                    }
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            };
+
+            supervisor.running.lock().unwrap().remove(&exited.instance_id);
+            let exit = GameExit {
+                instance_id: exited.instance_id.clone(),
+                version_id: exited.version_id.clone(),
+                pid: exited.pid,
+                exit_code: status.and_then(|s| s.code()),
+                runtime_secs: started.elapsed().as_secs(),
+                killed: *killed.lock().unwrap(),
+            };
+            println!("DEBUG: {} exited with code {:?} after {}s", exit.instance_id, exit.exit_code, exit.runtime_secs);
+            on_exit(&exit);
+            let _ = app.emit("game_exited", &exit);
+        });
+
+        info
+    }
+}
+
+impl Drop for LaunchSlot {
+    fn drop(&mut self) {
+        self.supervisor.starting.lock().unwrap().remove(&self.instance_id);
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use super::{adoptium_arch, adoptium_os};
+
+pub const ADOPTIUM_API_URL: &str = "https://api.adoptium.net/v3";
+
+/// One entry of `/v3/assets/latest/<major>/hotspot`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdoptiumRelease {
+    pub release_name: String,
+    pub binary: AdoptiumBinary,
+    pub version: AdoptiumVersion,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdoptiumBinary {
+    pub package: AdoptiumPackage,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdoptiumPackage {
+    pub name: String,
+    pub link: String,
+    /// SHA-256 of the archive
+    pub checksum: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdoptiumVersion {
+    pub openjdk_version: String,
+    pub semver: String,
+}
+
+/// Newest GA JRE build of a Java major version for this OS and architecture
+pub async fn latest_release(client: &reqwest::Client, major_version: u32) -> Result<AdoptiumRelease, String> {
+    let url = format!(
+        "{}/assets/latest/{}/hotspot?os={}&architecture={}&image_type=jre&vendor=eclipse",
+        ADOPTIUM_API_URL,
+        major_version,
+        adoptium_os(),
+        adoptium_arch()
+    );
+
+    let res = client.get(&url).send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(format!("Failed to query Adoptium for Java {}: HTTP {}", major_version, res.status()));
+    }
+    let releases: Vec<AdoptiumRelease> = res.json().await.map_err(|e| e.to_string())?;
+    releases
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Adoptium has no Java {} build for {} {}", major_version, adoptium_os(), adoptium_arch()))
+}
@@ -0,0 +1,275 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+use crate::process::hide_console;
+use super::{java_bin_path, JavaManager, JavaRuntime, JAVA_BINARY};
+
+/// Probe results keyed by canonical binary path, invalidated when the binary changes
+type ProbeCache = HashMap<PathBuf, (Option<SystemTime>, JavaRuntime)>;
+
+fn probe_cache() -> &'static Mutex<ProbeCache> {
+    static CACHE: OnceLock<Mutex<ProbeCache>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+impl JavaManager {
+    /// Every Java installation we can find on this machine, probed and deduplicated
+    pub fn detect_system_java(&self) -> Vec<JavaRuntime> {
+        let mut seen = HashSet::new();
+        let mut runtimes = Vec::new();
+
+        for candidate in self.java_candidates() {
+            let Ok(canonical) = fs::canonicalize(&candidate) else {
+                continue;
+            };
+            if !seen.insert(canonical.clone()) {
+                continue;
+            }
+            match probe_java(&candidate) {
+                Ok(runtime) => runtimes.push(runtime),
+                Err(e) => println!("WARN: Skipping Java at {}: {}", candidate.display(), e),
+            }
+        }
+
+        runtimes.sort_by(|a, b| b.major_version.cmp(&a.major_version).then(b.minor_version.cmp(&a.minor_version)));
+        println!("DEBUG: Found {} Java installations", runtimes.len());
+        runtimes
+    }
+
+    /// Java binaries worth probing, in order of preference
+    fn java_candidates(&self) -> Vec<PathBuf> {
+        let mut candidates = Vec::new();
+
+        if let Ok(java_home) = std::env::var("JAVA_HOME") {
+            candidates.push(java_bin_path(Path::new(&java_home)));
+        }
+
+        if let Some(path) = std::env::var_os("PATH") {
+            for dir in std::env::split_paths(&path) {
+                candidates.push(dir.join(JAVA_BINARY));
+            }
+        }
+
+        // Directories whose children are Java homes
+        for root in self.java_roots() {
+            let Ok(entries) = fs::read_dir(&root) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+                if let Ok(java) = self.find_java_exe_in_dir(&entry.path()) {
+                    candidates.push(PathBuf::from(java));
+                }
+            }
+        }
+
+        candidates.retain(|path| path.is_file());
+        candidates
+    }
+
+    fn java_roots(&self) -> Vec<PathBuf> {
+        let mut roots = vec![self.get_java_dir()];
+        let home = directories::UserDirs::new().map(|dirs| dirs.home_dir().to_path_buf());
+        let env_or_home = |var: &str, fallback: &[&str]| -> Option<PathBuf> {
+            std::env::var_os(var)
+                .map(PathBuf::from)
+                .or_else(|| home.as_ref().map(|h| fallback.iter().fold(h.clone(), |p, part| p.join(part))))
+        };
+
+        roots.extend(env_or_home("SDKMAN_DIR", &[".sdkman"]).map(|p| p.join("candidates").join("java")));
+        roots.extend(env_or_home("ASDF_DATA_DIR", &[".asdf"]).map(|p| p.join("installs").join("java")));
+        roots.extend(env_or_home("JABBA_HOME", &[".jabba"]).map(|p| p.join("jdk")));
+        roots.extend(env_or_home("GRADLE_USER_HOME", &[".gradle"]).map(|p| p.join("jdks")));
+
+        if cfg!(target_os = "windows") {
+            for var in ["ProgramFiles", "ProgramFiles(x86)", "ProgramW6432"] {
+                if let Some(program_files) = std::env::var_os(var).map(PathBuf::from) {
+                    for vendor in ["Java", "Eclipse Adoptium", "Eclipse Foundation", "AdoptOpenJDK", "Microsoft", "Zulu", "BellSoft", "Amazon Corretto", "Semeru"] {
+                        roots.push(program_files.join(vendor));
+                    }
+                }
+            }
+        } else if cfg!(target_os = "macos") {
+            roots.push(PathBuf::from("/Library/Java/JavaVirtualMachines"));
+            roots.push(PathBuf::from("/System/Library/Java/JavaVirtualMachines"));
+            roots.extend(home.as_ref().map(|h| h.join("Library/Java/JavaVirtualMachines")));
+        } else {
+            for root in ["/usr/lib/jvm", "/usr/lib64/jvm", "/usr/java", "/opt/java", "/opt/jdk"] {
+                roots.push(PathBuf::from(root));
+            }
+        }
+
+        roots
+    }
+}
+
+/// Run a Java binary with `-XshowSettings:properties` and read its version, vendor and
+/// architecture. Results are cached per binary until it is modified.
+pub fn probe_java(path: &Path) -> Result<JavaRuntime, String> {
+    let canonical = fs::canonicalize(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let modified = fs::metadata(&canonical).and_then(|m| m.modified()).ok();
+
+    if let Some((cached_at, runtime)) = probe_cache().lock().unwrap().get(&canonical) {
+        if *cached_at == modified {
+            return Ok(runtime.clone());
+        }
+    }
+
+    // javaw.exe has no console; java.exe next to it prints the same properties
+    let console_java = canonical.with_file_name(if cfg!(target_os = "windows") { "java.exe" } else { "java" });
+    let probe_bin = if console_java.is_file() { console_java } else { canonical.clone() };
+
+    let mut cmd = Command::new(&probe_bin);
+    cmd.args(["-XshowSettings:properties", "-version"]);
+    hide_console(&mut cmd);
+    let output = cmd.output().map_err(|e| e.to_string())?;
+    let text = String::from_utf8_lossy(&output.stderr);
+    let properties = parse_properties(&text);
+
+    let version = properties
+        .get("java.version")
+        .cloned()
+        .ok_or_else(|| format!("No java.version in output of {}", probe_bin.display()))?;
+    let (major_version, minor_version) = parse_java_version(&version);
+    let architecture = properties.get("os.arch").cloned().unwrap_or_else(|| "unknown".to_string());
+    let is_64bit = match properties.get("sun.arch.data.model").map(String::as_str) {
+        Some(model) => model == "64",
+        None => architecture.contains("64"),
+    };
+
+    let runtime = JavaRuntime {
+        version,
+        path: path.to_string_lossy().to_string(),
+        architecture,
+        major_version,
+        minor_version,
+        vendor: properties.get("java.vendor").cloned().unwrap_or_default(),
+        is_64bit,
+    };
+
+    probe_cache().lock().unwrap().insert(canonical, (modified, runtime.clone()));
+    Ok(runtime)
+}
+
+/// `key = value` lines from `-XshowSettings:properties`; multi-line values keep their first line
+fn parse_properties(text: &str) -> HashMap<String, String> {
+    text.lines()
+        .filter_map(|line| line.trim().split_once(" = "))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// Major and minor version from a `java.version`: "1.8.0_392" -> (8, 0), "17.0.9" -> (17, 0),
+/// "21" -> (21, 0)
+pub fn parse_java_version(version: &str) -> (u32, u32) {
+    let mut parts = version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<u32>().unwrap_or(0));
+    let first = parts.next().unwrap_or(0);
+    let second = parts.next().unwrap_or(0);
+    if first == 1 {
+        // Legacy "1.x" scheme
+        (second, parts.next().unwrap_or(0))
+    } else {
+        (first, second)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JAVA_8: &str = "Property settings:
+    awt.toolkit = sun.awt.X11.XToolkit
+    file.encoding = UTF-8
+    java.home = /usr/lib/jvm/java-8-openjdk-amd64/jre
+    java.runtime.version = 1.8.0_392-8u392-ga-1~22.04-b08
+    java.vendor = Private Build
+    java.version = 1.8.0_392
+    java.vm.version = 25.392-b08
+    line.separator = \\n
+    os.arch = amd64
+    os.name = Linux
+    sun.arch.data.model = 64
+
+openjdk version \"1.8.0_392\"
+OpenJDK Runtime Environment (build 1.8.0_392-8u392-ga-1~22.04-b08)
+OpenJDK 64-Bit Server VM (build 25.392-b08, mixed mode)
+";
+
+    const JAVA_17: &str = "Property settings:
+    file.encoding = UTF-8
+    java.class.path = 
+    java.home = C:\\Program Files\\Eclipse Adoptium\\jdk-17.0.9.9-hotspot
+    java.library.path = C:\\Program Files\\Eclipse Adoptium\\jdk-17.0.9.9-hotspot\\bin
+        C:\\Windows\\Sun\\Java\\bin
+        C:\\Windows\\system32
+    java.vendor = Eclipse Adoptium
+    java.version = 17.0.9
+    java.version.date = 2023-10-17
+    os.arch = amd64
+    os.name = Windows 11
+    sun.arch.data.model = 64
+
+openjdk version \"17.0.9\" 2023-10-17
+OpenJDK Runtime Environment Temurin-17.0.9+9 (build 17.0.9+9)
+OpenJDK 64-Bit Server VM Temurin-17.0.9+9 (build 17.0.9+9, mixed mode, sharing)
+";
+
+    const JAVA_21: &str = "Property settings:
+    java.home = /Library/Java/JavaVirtualMachines/zulu-21.jdk/Contents/Home
+    java.vendor = Azul Systems, Inc.
+    java.version = 21
+    java.version.date = 2023-09-19
+    os.arch = aarch64
+    os.name = Mac OS X
+    sun.arch.data.model = 64
+
+openjdk version \"21\" 2023-09-19 LTS
+OpenJDK Runtime Environment Zulu21.28+85-CA (build 21+35)
+OpenJDK 64-Bit Server VM Zulu21.28+85-CA (build 21+35, mixed mode, sharing)
+";
+
+    fn version_of(output: &str) -> (String, (u32, u32)) {
+        let properties = parse_properties(output);
+        let version = properties["java.version"].clone();
+        let parsed = parse_java_version(&version);
+        (version, parsed)
+    }
+
+    #[test]
+    fn parses_java_8_output() {
+        assert_eq!(version_of(JAVA_8), ("1.8.0_392".to_string(), (8, 0)));
+        let properties = parse_properties(JAVA_8);
+        assert_eq!(properties["java.vendor"], "Private Build");
+        assert_eq!(properties["os.arch"], "amd64");
+    }
+
+    #[test]
+    fn parses_java_17_output() {
+        assert_eq!(version_of(JAVA_17), ("17.0.9".to_string(), (17, 0)));
+        let properties = parse_properties(JAVA_17);
+        assert_eq!(properties["java.vendor"], "Eclipse Adoptium");
+        assert_eq!(properties["java.library.path"], "C:\\Program Files\\Eclipse Adoptium\\jdk-17.0.9.9-hotspot\\bin");
+    }
+
+    #[test]
+    fn parses_java_21_output() {
+        assert_eq!(version_of(JAVA_21), ("21".to_string(), (21, 0)));
+        assert_eq!(parse_properties(JAVA_21)["os.arch"], "aarch64");
+    }
+
+    #[test]
+    fn parses_version_strings() {
+        assert_eq!(parse_java_version("1.7.0_80"), (7, 0));
+        assert_eq!(parse_java_version("11.0.21"), (11, 0));
+        assert_eq!(parse_java_version("22-ea"), (22, 0));
+        assert_eq!(parse_java_version(""), (0, 0));
+    }
+}
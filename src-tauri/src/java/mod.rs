@@ -1,14 +1,37 @@
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
-use std::process::Command;
 use std::fs;
-use tauri::Emitter;
+use crate::game::Manifest;
+use crate::game::downloader::{emit_progress, DownloadJob, DownloadQueue, FileHash, DEFAULT_CONCURRENCY};
+
+pub mod adoptium;
+pub mod discovery;
+pub mod mojang;
+pub mod preflight;
+pub mod runtimes;
+
+/// Where managed Java runtimes come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JavaProvider {
+    /// Eclipse Temurin builds from the Adoptium API
+    #[default]
+    Adoptium,
+    /// Official runtime components from Mojang's java-runtime index
+    Mojang,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JavaRuntime {
+    /// Full `java.version`, e.g. "1.8.0_392" or "21.0.2"
     pub version: String,
     pub path: String,
+    /// `os.arch` reported by the JVM, e.g. "amd64" or "aarch64"
     pub architecture: String,
+    pub major_version: u32,
+    pub minor_version: u32,
+    pub vendor: String,
+    pub is_64bit: bool,
 }
 
 pub struct JavaManager {
@@ -24,81 +47,52 @@ impl JavaManager {
         self.base_dir.join("runtimes").join("java")
     }
 
-    /// Determine required Java version based on Minecraft version
-    pub fn determine_required_java_version(minecraft_version: &str) -> u32 {
-        // Parse version string (e.g., "1.8.9", "1.16.5", "1.20.1")
-        let parts: Vec<&str> = minecraft_version.split('.').collect();
-        
-        if parts.len() < 2 {
-            return 17; // Default to Java 17 for unknown versions
+    /// Required Java major version for a (merged) manifest: `javaVersion.majorVersion` when the
+    /// version JSON declares it, otherwise a guess from the Minecraft version
+    pub fn required_java_version(manifest: &Manifest) -> u32 {
+        if let Some(java_version) = &manifest.java_version {
+            return java_version.major_version;
         }
 
-        let major = parts[0].parse::<u32>().unwrap_or(1);
-        let minor = parts[1].parse::<u32>().unwrap_or(0);
-
-        // Version mapping
-        if major == 1 {
-            match minor {
-                0..=16 => 8,   // MC 1.0 - 1.16.x requires Java 8
-                17..=19 => 17, // MC 1.17 - 1.19.x requires Java 17
-                _ => 21,       // MC 1.20+ requires Java 21
-            }
-        } else {
-            21 // Future versions default to Java 21
-        }
+        let version = manifest
+            .minecraft_version
+            .as_deref()
+            .or(manifest.inherits_from.as_deref())
+            .unwrap_or(&manifest.id);
+        Self::determine_required_java_version(version)
     }
 
-    pub fn detect_system_java(&self) -> Vec<JavaRuntime> {
-        let mut runtimes = Vec::new();
-        
-        // Check JAVA_HOME
-        if let Ok(java_home) = std::env::var("JAVA_HOME") {
-            let path = PathBuf::from(java_home).join("bin").join("java.exe");
-            if path.exists() {
-                 if let Ok(version) = self.get_java_version(&path) {
-                     runtimes.push(JavaRuntime {
-                         version,
-                         path: path.to_string_lossy().to_string(),
-                         architecture: "unknown".to_string(),
-                     });
-                 }
-            }
-        }
-        
-        // Check local runtimes
-        let local_dir = self.get_java_dir();
-        if local_dir.exists() {
-             if let Ok(entries) = fs::read_dir(local_dir) {
-                 for entry in entries.flatten() {
-                      let path = entry.path().join("bin").join("java.exe");
-                      if path.exists() {
-                          if let Ok(version) = self.get_java_version(&path) {
-                             runtimes.push(JavaRuntime {
-                                 version,
-                                 path: path.to_string_lossy().to_string(),
-                                 architecture: "x64".to_string(),
-                             });
-                          }
-                      }
-                 }
-             }
+    /// Last-resort guess of the Java version from a version id.
+    /// Handles releases ("1.20.1"), modded ids ("fabric-loader-0.15-1.20.1") and snapshots ("24w14a").
+    pub fn determine_required_java_version(minecraft_version: &str) -> u32 {
+        // Weekly snapshots: YYwWWx
+        let snapshot = regex::Regex::new(r"(\d{2})w(\d{2})[a-z]").unwrap();
+        if let Some(caps) = snapshot.captures(minecraft_version) {
+            let year: u32 = caps[1].parse().unwrap_or(0);
+            let week: u32 = caps[2].parse().unwrap_or(0);
+            return match (year, week) {
+                (y, w) if y > 24 || (y == 24 && w >= 14) => 21, // 24w14a moved to Java 21
+                (y, w) if y >= 22 || (y == 21 && w >= 19) => 17,
+                _ => 8,
+            };
         }
 
-        runtimes
-    }
+        // Releases, possibly embedded in a loader id: the last whole 1.x(.y) in the string, so
+        // "1.20.1-forge-47.1.0" doesn't match the "1.0" inside the Forge version
+        let release = regex::Regex::new(r"(?:^|[^\d.])1\.(\d+)(?:\.(\d+))?(?:$|[^\d.])").unwrap();
+        let Some(caps) = release.captures_iter(minecraft_version).last() else {
+            return 21; // Unknown or future versions default to Java 21
+        };
+        let minor: u32 = caps[1].parse().unwrap_or(0);
+        let patch: u32 = caps.get(2).and_then(|p| p.as_str().parse().ok()).unwrap_or(0);
 
-    fn get_java_version(&self, path: &Path) -> Result<String, String> {
-        let output = Command::new(path)
-            .arg("-version")
-            .output()
-            .map_err(|e| e.to_string())?;
-            
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        if let Some(line) = stderr.lines().next() {
-            return Ok(line.to_string());
+        // Version mapping
+        match (minor, patch) {
+            (0..=16, _) => 8,     // MC 1.0 - 1.16.x requires Java 8
+            (17..=19, _) => 17,   // MC 1.17 - 1.19.x requires Java 17
+            (20, 0..=4) => 17,    // MC 1.20 - 1.20.4 requires Java 17
+            _ => 21,              // MC 1.20.5+ requires Java 21
         }
-        
-        Ok("Unknown".to_string())
     }
 
     /// Find or download the required Java version
@@ -117,72 +111,108 @@ impl JavaManager {
         self.download_java_runtime(major_version, app).await
     }
 
-    fn find_local_java(&self, major_version: u32) -> Option<String> {
-        let java_dir = self.get_java_dir();
-        let version_dir = java_dir.join(format!("jdk-{}", major_version));
-        let java_exe = version_dir.join("bin").join("java.exe");
-        
-        if java_exe.exists() {
-            return Some(java_exe.to_string_lossy().to_string());
+    /// Find or download a runtime for a manifest from the chosen provider. `component` is the
+    /// version's `javaVersion.component`; the Mojang provider derives one from the major
+    /// version when it is missing.
+    pub async fn ensure_runtime(
+        &self,
+        major_version: u32,
+        component: Option<&str>,
+        provider: JavaProvider,
+        app: tauri::AppHandle,
+    ) -> Result<String, String> {
+        match provider {
+            JavaProvider::Adoptium => self.ensure_java(major_version, app).await,
+            JavaProvider::Mojang => {
+                let component = component.unwrap_or_else(|| mojang::component_for_major(major_version));
+                // Launch offline from an installed runtime; verify_runtime re-checks its files
+                if let Some(path) = self.find_local_mojang_runtime(component) {
+                    println!("DEBUG: Found existing Java runtime {}: {}", component, path);
+                    return Ok(path);
+                }
+                self.install_mojang_runtime(component, app).await
+            }
         }
-        
-        None
     }
 
-    pub async fn download_java_runtime(&self, major_version: u32, app: tauri::AppHandle) -> Result<String, String> {
-        // Adoptium API endpoint
-        let url = format!(
-            "https://api.adoptium.net/v3/binary/latest/{}/ga/windows/x64/jre/hotspot/normal/eclipse",
-            major_version
-        );
-
-        println!("DEBUG: Downloading Java {} from Adoptium...", major_version);
-        let _ = app.emit("download_progress", serde_json::json!({
-            "file": format!("Java {}", major_version),
-            "percent": 0
-        }));
-
-        // Download the file
-        let client = reqwest::Client::new();
-        let response = client.get(&url)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to download Java: {}", e))?;
+    /// Download (or repair) a Mojang runtime component, reporting progress to the UI
+    pub async fn install_mojang_runtime(&self, component: &str, app: tauri::AppHandle) -> Result<String, String> {
+        let queue = DownloadQueue::new(DEFAULT_CONCURRENCY)
+            .with_label(format!("Java runtime {}", component))
+            .with_progress(emit_progress(app));
+        self.ensure_mojang_runtime(component, &queue).await
+    }
 
-        if !response.status().is_success() {
-            return Err(format!("Failed to download Java {}: HTTP {}", major_version, response.status()));
+    fn find_local_java(&self, major_version: u32) -> Option<String> {
+        let version_dir = self.get_java_dir().join(format!("jdk-{}", major_version));
+        if !version_dir.exists() {
+            return None;
         }
+        self.find_java_exe_in_dir(&version_dir).ok()
+    }
 
-        let total_size = response.content_length().unwrap_or(0);
-        let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+    /// Download the newest Adoptium JRE for a major version and install it as `jdk-<major>`.
+    ///
+    /// The archive is streamed to disk (resumable) and checked against the SHA-256 Adoptium
+    /// publishes, then extracted into a staging directory that replaces the old runtime in one
+    /// rename, so a failed install never leaves a half-extracted runtime behind.
+    pub async fn download_java_runtime(&self, major_version: u32, app: tauri::AppHandle) -> Result<String, String> {
+        let client = reqwest::Client::new();
+        let release = adoptium::latest_release(&client, major_version).await?;
+        let package = &release.binary.package;
+        let java_dir = self.get_java_dir();
 
-        let _ = app.emit("download_progress", serde_json::json!({
-            "file": format!("Java {}", major_version),
-            "percent": 100
-        }));
+        // 1. Download
+        println!("DEBUG: Downloading Java {} ({}) from Adoptium...", major_version, release.release_name);
+        let archive_path = java_dir.join(".downloads").join(&package.name);
+        let job = DownloadJob::new(&package.link, &archive_path, Some(FileHash::sha256(&package.checksum)), Some(package.size));
+        let queue = DownloadQueue::new(1)
+            .with_label(format!("Java {}", major_version))
+            .with_progress(emit_progress(app));
+        queue.run(&client, vec![job]).await.map_err(|e| format!("Failed to download Java {}: {}", major_version, e))?;
 
-        // Save to temp file
-        let temp_dir = std::env::temp_dir();
-        let zip_path = temp_dir.join(format!("java-{}.zip", major_version));
-        fs::write(&zip_path, &bytes).map_err(|e| e.to_string())?;
+        // 2. Extract into staging
+        let id = format!("jdk-{}", major_version);
+        let staging_dir = java_dir.join(format!(".staging-{}", id));
+        if staging_dir.exists() {
+            fs::remove_dir_all(&staging_dir).map_err(|e| e.to_string())?;
+        }
+        let extracted = self
+            .extract_archive(&archive_path, &staging_dir)
+            .and_then(|_| self.find_java_exe_in_dir(&staging_dir));
+        if let Err(e) = extracted {
+            let _ = fs::remove_dir_all(&staging_dir);
+            return Err(format!("Failed to extract Java {}: {}", major_version, e));
+        }
+        fs::write(staging_dir.join(".version"), &release.release_name).map_err(|e| e.to_string())?;
 
-        println!("DEBUG: Downloaded {} bytes to {:?}", bytes.len(), zip_path);
+        // 3. Swap into place
+        let target_dir = java_dir.join(&id);
+        swap_into_place(&staging_dir, &target_dir)?;
+        let _ = fs::remove_file(&archive_path);
 
-        // Extract
-        let java_dir = self.get_java_dir();
-        fs::create_dir_all(&java_dir).map_err(|e| e.to_string())?;
+        let java_exe = self.find_java_exe_in_dir(&target_dir)?;
+        println!("DEBUG: Java {} installed at: {}", major_version, java_exe);
+        Ok(java_exe)
+    }
 
-        let extract_dir = java_dir.join(format!("jdk-{}", major_version));
-        self.extract_zip(&zip_path, &extract_dir)?;
+    fn extract_archive(&self, archive_path: &Path, target_dir: &Path) -> Result<(), String> {
+        let name = archive_path.to_string_lossy();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            self.extract_tar_gz(archive_path, target_dir)
+        } else {
+            self.extract_zip(archive_path, target_dir)
+        }
+    }
 
-        // Clean up
-        let _ = fs::remove_file(&zip_path);
+    fn extract_tar_gz(&self, archive_path: &Path, target_dir: &Path) -> Result<(), String> {
+        println!("DEBUG: Extracting {:?} to {:?}", archive_path, target_dir);
 
-        // Find the java.exe in the extracted directory
-        let java_exe = self.find_java_exe_in_dir(&extract_dir)?;
-        
-        println!("DEBUG: Java {} installed at: {}", major_version, java_exe);
-        Ok(java_exe)
+        let file = fs::File::open(archive_path).map_err(|e| e.to_string())?;
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+        archive.set_preserve_permissions(true);
+        fs::create_dir_all(target_dir).map_err(|e| e.to_string())?;
+        archive.unpack(target_dir).map_err(|e| e.to_string())
     }
 
     fn extract_zip(&self, zip_path: &Path, target_dir: &Path) -> Result<(), String> {
@@ -206,6 +236,13 @@ impl JavaManager {
                 }
                 let mut outfile = fs::File::create(&outpath).map_err(|e| e.to_string())?;
                 std::io::copy(&mut file, &mut outfile).map_err(|e| e.to_string())?;
+
+                // Keep executable bits for zips built on unix
+                #[cfg(unix)]
+                if let Some(mode) = file.unix_mode() {
+                    use std::os::unix::fs::PermissionsExt;
+                    let _ = fs::set_permissions(&outpath, fs::Permissions::from_mode(mode));
+                }
             }
         }
 
@@ -213,19 +250,105 @@ impl JavaManager {
     }
 
     fn find_java_exe_in_dir(&self, dir: &Path) -> Result<String, String> {
-        // The extracted archive might have a nested structure like jdk-8.0.xxx/
-        // We need to find bin/java.exe recursively
-        for entry in walkdir::WalkDir::new(dir).max_depth(3) {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.file_name().and_then(|n| n.to_str()) == Some("java.exe") {
-                    if path.parent().and_then(|p| p.file_name()).and_then(|n| n.to_str()) == Some("bin") {
-                        return Ok(path.to_string_lossy().to_string());
-                    }
-                }
+        // The extracted archive might have a nested structure like jdk-8.0.xxx/ or, on macOS,
+        // jdk-17.0.x+y-jre/Contents/Home/bin/java. We need to find bin/<java> recursively
+        for entry in walkdir::WalkDir::new(dir).max_depth(5).into_iter().flatten() {
+            let path = entry.path();
+            if path.file_name().and_then(|n| n.to_str()) == Some(JAVA_BINARY)
+                && path.parent().and_then(|p| p.file_name()).and_then(|n| n.to_str()) == Some("bin")
+            {
+                return Ok(path.to_string_lossy().to_string());
             }
         }
         
-        Err("Could not find java.exe in extracted directory".to_string())
+        Err(format!("Could not find {} in extracted directory", JAVA_BINARY))
+    }
+}
+
+/// Replace `target` with `staging`, keeping the old directory until the new one is in place
+fn swap_into_place(staging: &Path, target: &Path) -> Result<(), String> {
+    let backup = target.with_file_name(format!(
+        ".old-{}",
+        target.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+    ));
+    if backup.exists() {
+        fs::remove_dir_all(&backup).map_err(|e| e.to_string())?;
+    }
+
+    let had_previous = target.exists();
+    if had_previous {
+        fs::rename(target, &backup).map_err(|e| e.to_string())?;
+    }
+    if let Err(e) = fs::rename(staging, target) {
+        if had_previous {
+            let _ = fs::rename(&backup, target);
+        }
+        return Err(format!("Failed to install {}: {}", target.display(), e));
+    }
+    if had_previous {
+        let _ = fs::remove_dir_all(&backup);
+    }
+    Ok(())
+}
+
+/// Java executable used to run the game; javaw avoids a console window on Windows
+pub const JAVA_BINARY: &str = if cfg!(target_os = "windows") { "javaw.exe" } else { "java" };
+
+/// `<java_home>/bin/<java>`
+pub fn java_bin_path(java_home: &Path) -> PathBuf {
+    java_home.join("bin").join(JAVA_BINARY)
+}
+
+/// Operating system name as used by the Adoptium API
+pub fn adoptium_os() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "mac"
+    } else {
+        "linux"
+    }
+}
+
+/// Architecture name as used by the Adoptium API
+pub fn adoptium_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "x86" => "x32",
+        "aarch64" => "aarch64",
+        "arm" => "arm",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn java_version_from_release_ids() {
+        assert_eq!(JavaManager::determine_required_java_version("1.8.9"), 8);
+        assert_eq!(JavaManager::determine_required_java_version("1.16.5"), 8);
+        assert_eq!(JavaManager::determine_required_java_version("1.18.2"), 17);
+        assert_eq!(JavaManager::determine_required_java_version("1.20.1"), 17);
+        assert_eq!(JavaManager::determine_required_java_version("1.20.5"), 21);
+        assert_eq!(JavaManager::determine_required_java_version("1.21"), 21);
+    }
+
+    #[test]
+    fn java_version_from_snapshots() {
+        assert_eq!(JavaManager::determine_required_java_version("24w14a"), 21);
+        assert_eq!(JavaManager::determine_required_java_version("24w13a"), 17);
+        assert_eq!(JavaManager::determine_required_java_version("21w19a"), 17);
+        assert_eq!(JavaManager::determine_required_java_version("20w45a"), 8);
+    }
+
+    #[test]
+    fn java_version_from_loader_ids() {
+        assert_eq!(JavaManager::determine_required_java_version("fabric-loader-0.15-1.20.1"), 17);
+        assert_eq!(JavaManager::determine_required_java_version("fabric-loader-0.15.11-1.20.6"), 21);
+        assert_eq!(JavaManager::determine_required_java_version("1.20.1-forge-47.1.0"), 17);
+        assert_eq!(JavaManager::determine_required_java_version("1.8.9-forge1.8.9-11.15.1.2318-1.8.9"), 8);
+        assert_eq!(JavaManager::determine_required_java_version("neoforge-21.1.1"), 21);
     }
 }
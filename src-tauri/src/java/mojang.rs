@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use crate::game::downloader::{DownloadJob, DownloadQueue, FileHash};
+use super::JavaManager;
+
+/// Index of every official runtime component per platform
+pub const RUNTIME_INDEX_URL: &str = "https://launchermeta.mojang.com/v1/products/java-runtime/2ec0cc96c44e5a76b9c8b7c39df7210883d12871/all.json";
+
+/// platform -> component -> available builds
+pub type RuntimeIndex = HashMap<String, HashMap<String, Vec<RuntimeEntry>>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeEntry {
+    pub manifest: RuntimeDownload,
+    pub version: RuntimeVersion,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeDownload {
+    pub sha1: String,
+    pub size: u64,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeVersion {
+    pub name: String,
+    pub released: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeManifest {
+    pub files: HashMap<String, RuntimeFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeFile {
+    #[serde(rename = "type")]
+    pub file_type: String,
+    #[serde(default)]
+    pub executable: bool,
+    pub downloads: Option<RuntimeFileDownloads>,
+    /// Symlink target, relative to the link
+    pub target: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeFileDownloads {
+    pub raw: RuntimeDownload,
+}
+
+/// Platform key used in the runtime index
+pub fn runtime_platform() -> &'static str {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("windows", "x86") => "windows-x86",
+        ("windows", "aarch64") => "windows-arm64",
+        ("windows", _) => "windows-x64",
+        ("macos", "aarch64") => "mac-os-arm64",
+        ("macos", _) => "mac-os",
+        ("linux", "x86") => "linux-i386",
+        _ => "linux",
+    }
+}
+
+/// Component the official launcher would use for a Java major version when the
+/// version JSON doesn't name one
+pub fn component_for_major(major_version: u32) -> &'static str {
+    match major_version {
+        0..=8 => "jre-legacy",
+        9..=16 => "java-runtime-alpha",
+        17 => "java-runtime-gamma",
+        _ => "java-runtime-delta",
+    }
+}
+
+/// Newest build of a component for this platform and its file manifest
+pub async fn fetch_runtime(client: &reqwest::Client, component: &str) -> Result<(RuntimeEntry, RuntimeManifest), String> {
+    // 1. Pick the build for this platform
+    let index: RuntimeIndex = fetch_json(client, RUNTIME_INDEX_URL).await?;
+    let entry = index
+        .get(runtime_platform())
+        .and_then(|components| components.get(component))
+        .and_then(|builds| builds.first())
+        .cloned()
+        .ok_or_else(|| format!("Java runtime {} is not available for {}", component, runtime_platform()))?;
+
+    // 2. File manifest
+    let manifest_bytes = client.get(&entry.manifest.url).send().await
+        .map_err(|e| e.to_string())?
+        .bytes().await
+        .map_err(|e| e.to_string())?;
+    if !FileHash::sha1(&entry.manifest.sha1).matches_bytes(&manifest_bytes) {
+        return Err(format!("Hash mismatch for {} runtime manifest", component));
+    }
+    let manifest: RuntimeManifest = serde_json::from_slice(&manifest_bytes).map_err(|e| e.to_string())?;
+    Ok((entry, manifest))
+}
+
+impl JavaManager {
+    /// Java binary of a component that finished installing, without touching the network
+    pub fn find_local_mojang_runtime(&self, component: &str) -> Option<String> {
+        let target_dir = self.get_java_dir().join(component);
+        // `.version` is only written once every file is in place
+        if !target_dir.join(".version").exists() {
+            return None;
+        }
+        self.find_java_exe_in_dir(&target_dir).ok()
+    }
+
+    /// Install (or verify) a Mojang runtime component into `runtimes/java/<component>` and
+    /// return the path of its java binary
+    pub async fn ensure_mojang_runtime(&self, component: &str, queue: &DownloadQueue) -> Result<String, String> {
+        let client = reqwest::Client::new();
+        let target_dir = self.get_java_dir().join(component);
+        let (entry, manifest) = fetch_runtime(&client, component).await?;
+
+        // Until the new build is complete the directory must not look installed
+        let version_file = target_dir.join(".version");
+        match fs::remove_file(&version_file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to remove {}: {}", version_file.display(), e)),
+        }
+
+        // Directories first, then files, then links
+        let mut jobs = Vec::new();
+        for (path, file) in &manifest.files {
+            let dest = target_dir.join(path);
+            match file.file_type.as_str() {
+                "directory" => fs::create_dir_all(&dest).map_err(|e| e.to_string())?,
+                "file" => {
+                    if let Some(downloads) = &file.downloads {
+                        let raw = &downloads.raw;
+                        jobs.push(DownloadJob::new(&raw.url, dest, Some(FileHash::sha1(&raw.sha1)), Some(raw.size)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        // Files that are already intact are skipped by the queue
+        println!("DEBUG: Checking {} files for Java runtime {}", jobs.len(), component);
+        queue.run(&client, jobs).await?;
+
+        for (path, file) in &manifest.files {
+            let dest = target_dir.join(path);
+            match (file.file_type.as_str(), &file.target) {
+                ("file", _) if file.executable => set_executable(&dest)?,
+                ("link", Some(link_target)) => create_symlink(link_target, &dest)?,
+                _ => {}
+            }
+        }
+
+        // Remember the exact build so installed runtimes can be listed later
+        fs::write(&version_file, &entry.version.name)
+            .map_err(|e| format!("Failed to write {}: {}", version_file.display(), e))?;
+
+        let java = self.find_java_exe_in_dir(&target_dir)?;
+        println!("DEBUG: Java runtime {} ({}) installed at: {}", component, entry.version.name, java);
+        Ok(java)
+    }
+
+    /// Files of an installed component that are missing or don't match the runtime manifest
+    pub async fn verify_mojang_runtime(&self, component: &str) -> Result<Vec<String>, String> {
+        let client = reqwest::Client::new();
+        let target_dir = self.get_java_dir().join(component);
+        let (_, manifest) = fetch_runtime(&client, component).await?;
+
+        // Hashing a whole runtime takes a while; keep it off the async workers
+        tokio::task::spawn_blocking(move || {
+            let mut broken: Vec<String> = manifest
+                .files
+                .iter()
+                .filter_map(|(path, file)| {
+                    let raw = &file.downloads.as_ref()?.raw;
+                    let job = DownloadJob::new(&raw.url, target_dir.join(path), Some(FileHash::sha1(&raw.sha1)), Some(raw.size));
+                    (file.file_type == "file" && !job.is_valid()).then(|| path.clone())
+                })
+                .collect();
+            broken.sort();
+            broken
+        })
+        .await
+        .map_err(|e| e.to_string())
+    }
+}
+
+async fn fetch_json<T: serde::de::DeserializeOwned>(client: &reqwest::Client, url: &str) -> Result<T, String> {
+    let res = client.get(url).send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(format!("Failed to fetch {}: {}", url, res.status()));
+    }
+    res.json().await.map_err(|e| e.to_string())
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).map_err(|e| e.to_string())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &str, link: &Path) -> Result<(), String> {
+    if fs::symlink_metadata(link).is_ok() {
+        fs::remove_file(link).map_err(|e| e.to_string())?;
+    }
+    if let Some(parent) = link.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::os::unix::fs::symlink(target, link).map_err(|e| e.to_string())
+}
+
+#[cfg(not(unix))]
+fn create_symlink(_target: &str, _link: &Path) -> Result<(), String> {
+    // Windows runtimes don't ship links
+    Ok(())
+}
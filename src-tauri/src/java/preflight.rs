@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use super::discovery::probe_java;
+use super::JavaRuntime;
+
+/// Largest heap a 32-bit JVM can reliably reserve
+const MAX_32BIT_HEAP_MB: u32 = 1536;
+
+/// Why a Java installation can't run a version; sent to the UI as the `java_preflight_failed` payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JavaIssue {
+    NotFound { path: String },
+    ProbeFailed { path: String, error: String },
+    TooOld { path: String, found: u32, required: u32 },
+    ArchMismatch { path: String, found: String, expected: String },
+    HeapTooLarge { path: String, max_memory: u32 },
+}
+
+impl fmt::Display for JavaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JavaIssue::NotFound { path } => write!(f, "Java not found at {}", path),
+            JavaIssue::ProbeFailed { path, error } => write!(f, "Could not run Java at {}: {}", path, error),
+            JavaIssue::TooOld { path, found, required } => {
+                write!(f, "Java {} at {} is too old, this version needs Java {} or newer", found, path, required)
+            }
+            JavaIssue::ArchMismatch { path, found, expected } => {
+                write!(f, "Java at {} is built for {}, but natives are for {}", path, found, expected)
+            }
+            JavaIssue::HeapTooLarge { path, max_memory } => {
+                write!(f, "Java at {} is 32-bit and cannot use {} MB of memory", path, max_memory)
+            }
+        }
+    }
+}
+
+/// Check that the Java at `path` can run a version needing `required` with `max_memory` MB
+pub fn check_java(path: &str, required: u32, max_memory: u32) -> Result<JavaRuntime, JavaIssue> {
+    if !Path::new(path).is_file() {
+        return Err(JavaIssue::NotFound { path: path.to_string() });
+    }
+
+    let runtime = probe_java(Path::new(path)).map_err(|error| JavaIssue::ProbeFailed { path: path.to_string(), error })?;
+    check_runtime(&runtime, required, max_memory)?;
+    Ok(runtime)
+}
+
+/// Check an already probed runtime against a version's requirements
+pub fn check_runtime(runtime: &JavaRuntime, required: u32, max_memory: u32) -> Result<(), JavaIssue> {
+    let path = runtime.path.as_str();
+    if runtime.major_version < required {
+        return Err(JavaIssue::TooOld { path: path.to_string(), found: runtime.major_version, required });
+    }
+
+    let found = normalize_arch(&runtime.architecture);
+    let expected = normalize_arch(std::env::consts::ARCH);
+    if found != expected {
+        return Err(JavaIssue::ArchMismatch { path: path.to_string(), found: found.to_string(), expected: expected.to_string() });
+    }
+
+    if !runtime.is_64bit && max_memory > MAX_32BIT_HEAP_MB {
+        return Err(JavaIssue::HeapTooLarge { path: path.to_string(), max_memory });
+    }
+
+    if required <= 8 && runtime.major_version > 8 {
+        println!("WARN: Running a Java 8 version on Java {}; older mod loaders may fail", runtime.major_version);
+    }
+
+    Ok(())
+}
+
+/// `os.arch` and Rust arch names mapped onto one vocabulary
+fn normalize_arch(arch: &str) -> &str {
+    match arch {
+        "amd64" | "x86_64" | "x64" => "x64",
+        "x86" | "i386" | "i486" | "i586" | "i686" => "x86",
+        "aarch64" | "arm64" => "arm64",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runtime(major_version: u32, architecture: &str, is_64bit: bool) -> JavaRuntime {
+        JavaRuntime {
+            version: format!("{}.0.1", major_version),
+            path: "/opt/java/bin/java".to_string(),
+            architecture: architecture.to_string(),
+            major_version,
+            minor_version: 0,
+            vendor: "Eclipse Adoptium".to_string(),
+            is_64bit,
+        }
+    }
+
+    /// The `os.arch` a JVM built for this machine would report
+    fn host_arch() -> &'static str {
+        match std::env::consts::ARCH {
+            "x86_64" => "amd64",
+            other => other,
+        }
+    }
+
+    #[test]
+    fn accepts_matching_runtime() {
+        assert!(check_runtime(&runtime(21, host_arch(), true), 17, 4096).is_ok());
+    }
+
+    #[test]
+    fn rejects_old_java() {
+        match check_runtime(&runtime(8, host_arch(), true), 17, 2048) {
+            Err(JavaIssue::TooOld { found, required, .. }) => assert_eq!((found, required), (8, 17)),
+            other => panic!("expected TooOld, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_other_architecture() {
+        let foreign = if normalize_arch(std::env::consts::ARCH) == "arm64" { "amd64" } else { "aarch64" };
+        match check_runtime(&runtime(17, foreign, true), 17, 2048) {
+            Err(JavaIssue::ArchMismatch { found, expected, .. }) => {
+                assert_eq!(found, normalize_arch(foreign));
+                assert_eq!(expected, normalize_arch(std::env::consts::ARCH));
+            }
+            other => panic!("expected ArchMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_large_heap_on_32bit() {
+        match check_runtime(&runtime(17, host_arch(), false), 17, 4096) {
+            Err(JavaIssue::HeapTooLarge { max_memory, .. }) => assert_eq!(max_memory, 4096),
+            other => panic!("expected HeapTooLarge, got {:?}", other),
+        }
+        assert!(check_runtime(&runtime(17, host_arch(), false), 17, MAX_32BIT_HEAP_MB).is_ok());
+    }
+}
@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use super::discovery::probe_java;
+use super::{adoptium, mojang, JavaManager, JavaProvider};
+
+/// A runtime the launcher installed under `runtimes/java/`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagedRuntime {
+    /// Directory name: `jdk-<major>` for Adoptium, the component name for Mojang
+    pub id: String,
+    pub provider: JavaProvider,
+    pub major_version: u32,
+    /// Exact build, e.g. "jdk-17.0.9+9" or "17.0.8"
+    pub release: Option<String>,
+    pub path: String,
+    pub size: u64,
+    /// Profiles that would launch with this runtime
+    #[serde(default)]
+    pub used_by: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeUpdate {
+    pub id: String,
+    pub installed: Option<String>,
+    pub latest: String,
+    pub update_available: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeCheck {
+    pub id: String,
+    pub ok: bool,
+    pub problems: Vec<String>,
+}
+
+impl JavaManager {
+    /// Managed runtime id that `ensure_runtime` installs for these inputs
+    pub fn runtime_id(major_version: u32, component: Option<&str>, provider: JavaProvider) -> String {
+        match provider {
+            JavaProvider::Adoptium => format!("jdk-{}", major_version),
+            JavaProvider::Mojang => component.unwrap_or_else(|| mojang::component_for_major(major_version)).to_string(),
+        }
+    }
+
+    /// Managed runtime id a java binary belongs to, if it lives under `runtimes/java/`
+    pub fn runtime_id_for_path(&self, java_path: &str) -> Option<String> {
+        let relative = Path::new(java_path).strip_prefix(self.get_java_dir()).ok()?;
+        relative.components().next().map(|c| c.as_os_str().to_string_lossy().to_string())
+    }
+
+    /// Every runtime installed under `runtimes/java/`; `used_by` is left for the caller to fill
+    pub fn list_managed_runtimes(&self) -> Vec<ManagedRuntime> {
+        let mut runtimes = Vec::new();
+        let Ok(entries) = fs::read_dir(self.get_java_dir()) else {
+            return runtimes;
+        };
+
+        for entry in entries.flatten() {
+            let id = entry.file_name().to_string_lossy().to_string();
+            // Skip downloads, staging and backup directories
+            if !entry.path().is_dir() || id.starts_with('.') {
+                continue;
+            }
+            match self.managed_runtime(&id) {
+                Ok(runtime) => runtimes.push(runtime),
+                Err(e) => println!("WARN: Skipping Java runtime {}: {}", id, e),
+            }
+        }
+
+        runtimes.sort_by(|a, b| a.id.cmp(&b.id));
+        runtimes
+    }
+
+    pub fn managed_runtime(&self, id: &str) -> Result<ManagedRuntime, String> {
+        let dir = self.runtime_dir(id)?;
+        let path = self.find_java_exe_in_dir(&dir)?;
+        let provider = if id.starts_with("jdk-") { JavaProvider::Adoptium } else { JavaProvider::Mojang };
+        let major_version = match probe_java(Path::new(&path)) {
+            Ok(runtime) => runtime.major_version,
+            Err(_) => id.trim_start_matches("jdk-").parse().unwrap_or(0),
+        };
+        let size = walkdir::WalkDir::new(&dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.metadata().ok())
+            .filter(|m| m.is_file())
+            .map(|m| m.len())
+            .sum();
+
+        Ok(ManagedRuntime {
+            id: id.to_string(),
+            provider,
+            major_version,
+            release: installed_release(&dir, Path::new(&path)),
+            path,
+            size,
+            used_by: Vec::new(),
+        })
+    }
+
+    /// Compare an installed runtime with the newest build its provider offers
+    pub async fn check_runtime_update(&self, id: &str) -> Result<RuntimeUpdate, String> {
+        let runtime = self.managed_runtime(id)?;
+        let client = reqwest::Client::new();
+
+        let (latest, up_to_date) = match runtime.provider {
+            JavaProvider::Adoptium => {
+                let release = adoptium::latest_release(&client, runtime.major_version).await?;
+                let current = runtime.release.as_deref();
+                let up_to_date = current == Some(release.release_name.as_str()) || current == Some(release.version.openjdk_version.as_str());
+                (release.release_name, up_to_date)
+            }
+            JavaProvider::Mojang => {
+                let (entry, _) = mojang::fetch_runtime(&client, id).await?;
+                let up_to_date = runtime.release.as_deref() == Some(entry.version.name.as_str());
+                (entry.version.name, up_to_date)
+            }
+        };
+
+        Ok(RuntimeUpdate {
+            id: id.to_string(),
+            installed: runtime.release,
+            latest,
+            update_available: !up_to_date,
+        })
+    }
+
+    /// Check that a runtime still starts and, for Mojang components, that every file matches its hash
+    pub async fn verify_runtime(&self, id: &str) -> Result<RuntimeCheck, String> {
+        let runtime = self.managed_runtime(id)?;
+        let mut problems = Vec::new();
+
+        match probe_java(Path::new(&runtime.path)) {
+            Ok(probed) if probed.major_version != runtime.major_version => {
+                problems.push(format!("Reports Java {} instead of {}", probed.major_version, runtime.major_version));
+            }
+            Ok(_) => {}
+            Err(e) => problems.push(format!("Does not start: {}", e)),
+        }
+
+        if runtime.provider == JavaProvider::Mojang {
+            for file in self.verify_mojang_runtime(id).await? {
+                problems.push(format!("Missing or corrupted: {}", file));
+            }
+        }
+
+        Ok(RuntimeCheck { id: id.to_string(), ok: problems.is_empty(), problems })
+    }
+
+    /// Reinstall a runtime from the newest build of its provider
+    pub async fn update_runtime(&self, id: &str, app: tauri::AppHandle) -> Result<String, String> {
+        let runtime = self.managed_runtime(id)?;
+        match runtime.provider {
+            JavaProvider::Adoptium => self.download_java_runtime(runtime.major_version, app).await,
+            JavaProvider::Mojang => self.install_mojang_runtime(id, app).await,
+        }
+    }
+
+    /// Delete a runtime. Refuses while profiles still use it unless `force` is set.
+    pub fn uninstall_runtime(&self, id: &str, used_by: &[String], force: bool) -> Result<(), String> {
+        let dir = self.runtime_dir(id)?;
+        if !used_by.is_empty() && !force {
+            return Err(format!("Java runtime {} is used by: {}", id, used_by.join(", ")));
+        }
+        fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+        println!("DEBUG: Uninstalled Java runtime {}", id);
+        Ok(())
+    }
+
+    fn runtime_dir(&self, id: &str) -> Result<PathBuf, String> {
+        if id.is_empty() || id.contains(['/', '\\']) || id.starts_with('.') {
+            return Err(format!("Invalid Java runtime id: {}", id));
+        }
+        let dir = self.get_java_dir().join(id);
+        if !dir.is_dir() {
+            return Err(format!("Java runtime {} is not installed", id));
+        }
+        Ok(dir)
+    }
+}
+
+/// `.version` written at install time, otherwise `JAVA_RUNTIME_VERSION` from the JDK `release` file
+fn installed_release(dir: &Path, java_path: &Path) -> Option<String> {
+    if let Ok(version) = fs::read_to_string(dir.join(".version")) {
+        return Some(version.trim().to_string());
+    }
+
+    // <home>/bin/java -> <home>/release
+    let home = java_path.parent()?.parent()?;
+    let release = fs::read_to_string(home.join("release")).ok()?;
+    let value = |key: &str| {
+        release.lines().find_map(|line| {
+            line.strip_prefix(key)?.strip_prefix('=').map(|v| v.trim_matches('"').to_string())
+        })
+    };
+    value("JAVA_RUNTIME_VERSION").or_else(|| value("JAVA_VERSION"))
+}
@@ -5,6 +5,7 @@ pub mod mods;
 pub mod java;
 pub mod api;
 pub mod settings;
+pub mod process;
 
 use auth::{Account, microsoft};
 use profiles::{Profile, ProfileManager};
@@ -13,7 +14,7 @@ use java::{JavaRuntime, JavaManager};
 use api::AppState;
 use settings::{Settings, SettingsManager};
 use std::sync::{Arc, Mutex};
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
 #[tauri::command]
 async fn start_ms_auth() -> Result<microsoft::DeviceCodeResponse, microsoft::AuthError> {
@@ -40,37 +41,91 @@ async fn refresh_ms_token(refresh_token: String) -> Result<microsoft::MicrosoftA
 }
 
 #[tauri::command]
-async fn install_game(manifest_url: String, app_handle: tauri::AppHandle) -> Result<String, String> {
+async fn install_game(version_id: String, manifest_url: Option<String>, assets_url: Option<String>, app_handle: tauri::AppHandle) -> Result<String, String> {
     use tauri::Manager;
     let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
     
     // Load settings to get preferred Minecraft dir
     let settings_mgr = crate::settings::SettingsManager::new(app_dir.clone());
     let settings = settings_mgr.load();
-    let mc_dir = match settings.minecraft_dir {
-        Some(dir) => std::path::PathBuf::from(dir),
-        None => game::scanner::find_minecraft_dir(),
-    };
+    let mc_dir = minecraft_dir(&settings);
+
+    let mut game_mgr = crate::game::GameManager::new(mc_dir);
+    if let Some(assets_url) = assets_url {
+        game_mgr = game_mgr.with_assets_base_url(assets_url);
+    }
+    let client = reqwest::Client::new();
+    let manifest_url = manifest_url.unwrap_or_else(|| game::installer::VERSION_MANIFEST_URL.to_string());
+    let queue = game::downloader::DownloadQueue::new(settings.download_concurrency)
+        .with_label(version_id.clone())
+        .with_progress(game::downloader::emit_progress(app_handle.clone()));
+
+    let manifest = game_mgr.install_version(&client, &queue, &manifest_url, &version_id).await?;
+    Ok(format!("Installed {}", manifest.id))
+}
+
+#[tauri::command]
+async fn repair_game(version_id: String, app_handle: tauri::AppHandle) -> Result<String, String> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let settings = SettingsManager::new(app_dir).load();
+    let mc_dir = minecraft_dir(&settings);
 
     let game_mgr = crate::game::GameManager::new(mc_dir);
-    
-    // TEMPORARILY DISABLED - install_version uses old manifest format
-    return Err("Game installation is temporarily disabled. Please use the official Minecraft launcher to install versions.".to_string());
-    // let manifest = game_mgr.install_version(&manifest_url, &app_handle).await?;
-    // Ok(format!("Installed {}", manifest.id))
+    let client = reqwest::Client::new();
+    let queue = game::downloader::DownloadQueue::new(settings.download_concurrency)
+        .with_label(version_id.clone())
+        .with_progress(game::downloader::emit_progress(app_handle.clone()));
+
+    let manifest = game_mgr.repair_version(&client, &queue, &version_id).await?;
+    Ok(format!("Repaired {}", manifest.id))
+}
+
+#[tauri::command]
+async fn launch_profile(profile_id: String, account: Account, app_handle: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let profile = ProfileManager::new(app_dir)
+        .profiles
+        .remove(&profile_id)
+        .ok_or_else(|| format!("Profile {} not found", profile_id))?;
+    let manifest_id = profile.version_id.clone();
+    launch(Some(profile), manifest_id, None, account, app_handle, &state).await
 }
 
+/// Launch a profile and join `server` (`host[:port]`) as soon as the game is up
+#[tauri::command]
+async fn launch_into_server(profile_id: String, server: String, account: Account, app_handle: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let quick_play = game::launcher::QuickPlay::server(&server)?;
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let profile = ProfileManager::new(app_dir)
+        .profiles
+        .remove(&profile_id)
+        .ok_or_else(|| format!("Profile {} not found", profile_id))?;
+    let manifest_id = profile.version_id.clone();
+    launch(Some(profile), manifest_id, Some(quick_play), account, app_handle, &state).await
+}
+
+/// Launch a version directly, with the options of the first profile on that version if any.
+/// Profiles sharing a version can't be told apart here; use `launch_profile` for those.
 #[tauri::command]
 async fn launch_game_cmd(manifest_id: String, account: Account, app_handle: tauri::AppHandle, state: tauri::State<'_, AppState>) -> Result<(), String> {
     let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let profile = ProfileManager::new(app_dir)
+        .list_profiles()
+        .into_iter()
+        .find(|p| p.version_id == manifest_id);
+    launch(profile, manifest_id, None, account, app_handle, &state).await
+}
+
+/// Everything (Java, memory, resolution, JVM args, mods and game directory) comes from
+/// `profile`, or from the global settings when launching without one. `quick_play` replaces
+/// the profile's own target for this launch.
+async fn launch(profile: Option<Profile>, manifest_id: String, quick_play: Option<game::launcher::QuickPlay>, account: Account, app_handle: tauri::AppHandle, state: &AppState) -> Result<(), String> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
     
     // Load settings
     let settings_mgr = crate::settings::SettingsManager::new(app_dir.clone());
     let settings = settings_mgr.load();
-    let mc_dir = match settings.minecraft_dir {
-        Some(dir) => std::path::PathBuf::from(dir),
-        None => game::scanner::find_minecraft_dir(),
-    };
+    let mc_dir = minecraft_dir(&settings);
 
     // Load manifest
     println!("DEBUG: Loading manifest for {}", manifest_id);
@@ -78,21 +133,14 @@ async fn launch_game_cmd(manifest_id: String, account: Account, app_handle: taur
     let manifest = game_manager.load_manifest(&manifest_id).await.map_err(|e| e.to_string())?;
     println!("DEBUG: Manifest loaded successfully for {}", manifest_id);
 
-    // We assume the manifest_id passed IS the version_id from the profile
-    // Ideally we should lookup the profile by ID if passed, but for now we look up the profile that matches this version 
-    // or just use default options if not found.
-    // Real implementation: launch_game_cmd(profile_id, account)
-    
-    // Let's try to find if a profile exists for this version to apply overrides
     let profile_manager = ProfileManager::new(app_dir.clone());
-    let profiles = profile_manager.list_profiles();
-    let profile = profiles.into_iter().find(|p| p.version_id == manifest_id);
 
-    // Load settings
-    let settings_mgr = crate::settings::SettingsManager::new(app_dir.clone());
-    let settings = settings_mgr.load();
+    // One process per instance; released again if anything below fails before the spawn
+    let instance_id = profile.as_ref().map(|p| p.id.clone()).unwrap_or_else(|| manifest_id.clone());
+    let instance_name = profile.as_ref().map(|p| p.name.clone()).unwrap_or_else(|| manifest_id.clone());
+    let launch_slot = state.supervisor.begin_launch(&instance_id)?;
 
-    let (min_mem, max_mem, width, height, _java_args, java_path) = if let Some(p) = &profile {
+    let (min_mem, max_mem, width, height, java_args, java_path) = if let Some(p) = &profile {
         (p.min_memory, p.max_memory, p.width, p.height, p.java_args.clone(), p.java_path.clone())
     } else {
         (settings.min_memory, settings.max_memory, settings.width, settings.height, "-XX:+UseG1GC".to_string(), settings.java_path)
@@ -100,20 +148,85 @@ async fn launch_game_cmd(manifest_id: String, account: Account, app_handle: taur
 
     // Determine required Java version and ensure it's available
     let java_manager = crate::java::JavaManager::new(app_dir.clone());
-    let required_java_version = crate::java::JavaManager::determine_required_java_version(&manifest_id);
+    let required_java_version = crate::java::JavaManager::required_java_version(&manifest);
     
     println!("DEBUG: Minecraft {} requires Java {}", manifest_id, required_java_version);
     
-    let java_path_final = if let Some(custom_path) = java_path {
-        // User has specified a custom Java path, use it
-        println!("DEBUG: Using custom Java path: {}", custom_path);
-        custom_path
-    } else {
-        // Auto-detect or download required Java
-        println!("DEBUG: Auto-detecting/downloading Java {}...", required_java_version);
-        java_manager.ensure_java(required_java_version, app_handle.clone())
+    let provider = profile.as_ref().and_then(|p| p.java_provider).unwrap_or(settings.java_provider);
+    let component = manifest.java_version.as_ref().and_then(|j| j.component.as_deref());
+    let custom_java = match java_path {
+        Some(custom_path) => {
+            // User has specified a custom Java path, check it can run this version
+            println!("DEBUG: Using custom Java path: {}", custom_path);
+            // Probing runs `java`; keep it off the async workers
+            let check_path = custom_path.clone();
+            let check = tauri::async_runtime::spawn_blocking(move || {
+                crate::java::preflight::check_java(&check_path, required_java_version, max_mem)
+            })
             .await
-            .map_err(|e| format!("Failed to ensure Java {}: {}", required_java_version, e))?
+            .map_err(|e| e.to_string())?;
+            match check {
+                Ok(_) => Some(custom_path),
+                Err(issue) if profile.as_ref().is_some_and(|p| p.java_fallback) => {
+                    println!("WARN: {}, falling back to a managed runtime", issue);
+                    None
+                }
+                Err(issue) => {
+                    let _ = app_handle.emit("java_preflight_failed", &issue);
+                    return Err(issue.to_string());
+                }
+            }
+        }
+        None => None,
+    };
+
+    let java_path_final = match custom_java {
+        Some(path) => path,
+        None => {
+            // Auto-detect or download required Java
+            println!("DEBUG: Auto-detecting/downloading Java {}...", required_java_version);
+            java_manager.ensure_runtime(required_java_version, component, provider, app_handle.clone())
+                .await
+                .map_err(|e| format!("Failed to ensure Java {}: {}", required_java_version, e))?
+        }
+    };
+
+    // JVM flags are checked against the Java that will actually run
+    let probe_path = std::path::PathBuf::from(&java_path_final);
+    let java_major = tauri::async_runtime::spawn_blocking(move || crate::java::discovery::probe_java(&probe_path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|runtime| runtime.major_version)
+        .unwrap_or(required_java_version);
+    let jvm_preset = profile.as_ref().and_then(|p| p.jvm_preset);
+    let mut jvm_args = game::jvm_args::build_jvm_args(jvm_preset, &java_args, java_major)?;
+
+    // Logging config and Log4Shell mitigation come first so profile flags can still override them
+    let client = reqwest::Client::new();
+    let queue = game::downloader::DownloadQueue::new(settings.download_concurrency)
+        .with_label(format!("{} files", manifest_id))
+        .with_progress(game::downloader::emit_progress(app_handle.clone()));
+    let logging_args = match game_manager.ensure_logging_config(&client, &queue, &manifest).await {
+        Ok(args) => args,
+        // log4j before 2.10 ignores the no-lookups flag; launching without the patched config
+        // would leave the game open to Log4Shell
+        Err(e) if matches!(game::logging::log4shell_mitigation(&manifest), game::logging::Log4ShellMitigation::PatchedConfig(_)) => {
+            return Err(format!("Could not download the Log4Shell-patched logging config for {}: {}", manifest_id, e));
+        }
+        Err(e) => {
+            println!("WARN: Could not set up logging config for {}: {}", manifest_id, e);
+            vec![game::logging::NO_LOOKUPS_ARG.to_string()]
+        }
+    };
+    jvm_args.splice(0..0, logging_args);
+
+    // Instances get their own game directory; libraries, assets and versions stay in mc_dir
+    let (game_dir, mods_dir) = match &profile {
+        Some(p) => (
+            profile_manager.prepare_game_dir(p, &mc_dir)?,
+            Some(p.mods_dir(&profile_manager.instances_dir(), &mc_dir)),
+        ),
+        None => (mc_dir.clone(), None),
     };
 
     let options = crate::game::launcher::LaunchOptions {
@@ -122,6 +235,11 @@ async fn launch_game_cmd(manifest_id: String, account: Account, app_handle: taur
         width,
         height,
         java_path: java_path_final,
+        game_dir: game_dir.clone(),
+        jvm_args,
+        full_screen: profile.as_ref().and_then(|p| p.full_screen).unwrap_or(settings.full_screen),
+        demo: profile.as_ref().is_some_and(|p| p.demo),
+        quick_play: quick_play.or_else(|| profile.as_ref().and_then(|p| p.quick_play.clone())),
     };
     
     // Use mc_dir for mods as well if possible
@@ -130,23 +248,66 @@ async fn launch_game_cmd(manifest_id: String, account: Account, app_handle: taur
         *profile_lock = Some(p.clone());
         
         let mod_manager = ModManager::new(app_dir.clone());
-        let mods_dir = mc_dir.join("versions").join(&p.version_id).join("mods");
-        if let Ok(mods) = mod_manager.scan_mods(&mods_dir) {
+        if let Some(Ok(mods)) = mods_dir.as_ref().map(|dir| mod_manager.scan_mods(dir)) {
              let mut mods_lock = state.active_mods.lock().unwrap();
              *mods_lock = mods.into_iter().filter(|m| m.enabled).collect::<Vec<Mod>>();
         }
     }
 
-    // Extract natives before launch
+    // Make sure assets are present (downloads anything missing)
+    game_manager.ensure_assets(&client, &queue, &manifest, &game_dir, false).await?;
+
     // Extract natives before launch
     println!("DEBUG: Extracting natives for {}", manifest_id);
     game_manager.extract_natives(&manifest).map_err(|e| e.to_string())?;
     println!("DEBUG: Natives extracted successfully");
 
-    crate::game::launcher::launch_game(&mc_dir, &manifest, &account, &options, app_handle)?;
+    let launch_started = std::time::SystemTime::now();
+    let output_tail = game::crash::OutputTail::default();
+    let game_log = game::logs::GameLog::create(&app_dir.join("logs"), &instance_id, output_tail.clone(), app_handle.clone());
+    let child = crate::game::launcher::launch_game(&mc_dir, &manifest, &account, &options, &game_log, app_handle.clone())?;
+
+    // Diagnose non-zero exits from the crash report and the last lines of output
+    let crash_app = app_handle.clone();
+    let mods_base = app_dir.clone();
+    launch_slot.track(&instance_name, &manifest.id, child, app_handle, move |exit| {
+        if exit.killed || exit.exit_code == Some(0) {
+            return;
+        }
+        let report = game::crash::find_crash_report(&game_dir, launch_started);
+        let mods = mods_dir
+            .and_then(|dir| ModManager::new(mods_base).scan_mods(&dir).ok())
+            .unwrap_or_default();
+        let output: Vec<String> = output_tail.lock().unwrap().iter().cloned().collect();
+        let diagnosis = game::crash::diagnose(&exit.instance_id, &exit.version_id, exit.exit_code, report.as_deref(), &output, &mods);
+        println!("DEBUG: Crash diagnosis for {}: {:?}", exit.instance_id, diagnosis.cause);
+        let _ = crash_app.emit("game_crashed", &diagnosis);
+    });
     Ok(())
 }
 
+#[tauri::command]
+async fn kill_game(instance_id: String, state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.supervisor.kill(&instance_id)
+}
+
+#[tauri::command]
+async fn list_game_logs(app_handle: tauri::AppHandle) -> Result<Vec<game::logs::LogFile>, String> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(game::logs::list_log_files(&app_dir.join("logs")))
+}
+
+#[tauri::command]
+async fn read_game_log(name: String, app_handle: tauri::AppHandle) -> Result<Vec<game::logs::LogRecord>, String> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    game::logs::read_log_file(&app_dir.join("logs"), &name)
+}
+
+#[tauri::command]
+async fn list_running(state: tauri::State<'_, AppState>) -> Result<Vec<game::supervisor::RunningInstance>, String> {
+    Ok(state.supervisor.list_running())
+}
+
 #[tauri::command]
 async fn get_profiles(app_handle: tauri::AppHandle) -> Result<Vec<Profile>, String> {
     use tauri::Manager;
@@ -159,32 +320,115 @@ async fn get_profiles(app_handle: tauri::AppHandle) -> Result<Vec<Profile>, Stri
 async fn create_profile(name: String, version_id: String, app_handle: tauri::AppHandle) -> Result<Profile, String> {
     use tauri::Manager;
     let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let mut manager = ProfileManager::new(app_dir);
-    Ok(manager.create_profile(name, version_id))
+    let mut manager = ProfileManager::new(app_dir.clone());
+    let profile = manager.create_profile(name, version_id);
+    sync_official(&app_dir, &mut manager);
+    Ok(profile)
 }
 
 #[tauri::command]
 async fn update_profile(profile: Profile, app_handle: tauri::AppHandle) -> Result<(), String> {
     use tauri::Manager;
     let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let mut manager = ProfileManager::new(app_dir);
-    manager.update_profile(profile)
+    let mut manager = ProfileManager::new(app_dir.clone());
+    manager.update_profile(profile)?;
+    sync_official(&app_dir, &mut manager);
+    Ok(())
 }
 
 #[tauri::command]
 async fn delete_profile(id: String, app_handle: tauri::AppHandle) -> Result<(), String> {
     use tauri::Manager;
     let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let mut manager = ProfileManager::new(app_dir);
+    let mut manager = ProfileManager::new(app_dir.clone());
+    let settings = SettingsManager::new(app_dir).load();
+    if let Some(official_id) = manager.profiles.get(&id).and_then(|p| p.official_id.clone()) {
+        if settings.sync_official_profiles {
+            if let Err(e) = manager.remove_official(&minecraft_dir(&settings), &official_id) {
+                println!("WARN: Could not remove {} from launcher_profiles.json: {}", official_id, e);
+            }
+        }
+    }
     manager.delete_profile(&id)
 }
 
+/// Import the official launcher's profiles, updating the ones imported before
+#[tauri::command]
+async fn import_official_profiles(app_handle: tauri::AppHandle) -> Result<Vec<Profile>, String> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let settings = SettingsManager::new(app_dir.clone()).load();
+    let mut manager = ProfileManager::new(app_dir);
+    import_official(&mut manager, &minecraft_dir(&settings)).await
+}
+
+/// Write our profiles into the official launcher's `launcher_profiles.json`
 #[tauri::command]
-async fn get_mods(version_id: String, app_handle: tauri::AppHandle) -> Result<Vec<Mod>, String> {
+async fn export_official_profiles(app_handle: tauri::AppHandle) -> Result<usize, String> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let settings = SettingsManager::new(app_dir.clone()).load();
+    let mut manager = ProfileManager::new(app_dir);
+    manager.export_official(&minecraft_dir(&settings))
+}
+
+fn minecraft_dir(settings: &crate::settings::Settings) -> std::path::PathBuf {
+    match &settings.minecraft_dir {
+        Some(dir) => std::path::PathBuf::from(dir),
+        None => game::scanner::find_minecraft_dir(),
+    }
+}
+
+/// `latest-*` profiles need the version list; it is only fetched when one is present
+async fn import_official(manager: &mut ProfileManager, mc_dir: &std::path::Path) -> Result<Vec<Profile>, String> {
+    let official = profiles::official::read_launcher_profiles(mc_dir)?;
+    let latest = if official.profiles.values().any(|p| p.is_latest()) {
+        let client = reqwest::Client::new();
+        match game::installer::fetch_version_manifest(&client, game::installer::VERSION_MANIFEST_URL).await {
+            Ok(versions) => Some(versions.latest),
+            Err(e) => {
+                println!("WARN: Could not resolve latest versions: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    manager.import_official(mc_dir, latest.as_ref())
+}
+
+/// Pull in changes made in the official launcher since the last start, when syncing is on
+async fn sync_official_on_start(app_handle: tauri::AppHandle) {
+    use tauri::Manager;
+    let Ok(app_dir) = app_handle.path().app_data_dir() else {
+        return;
+    };
+    let settings = SettingsManager::new(app_dir.clone()).load();
+    if settings.sync_official_profiles {
+        let mut manager = ProfileManager::new(app_dir);
+        if let Err(e) = import_official(&mut manager, &minecraft_dir(&settings)).await {
+            println!("WARN: Could not sync profiles from the official launcher: {}", e);
+        }
+    }
+}
+
+/// Push profile changes to the official launcher when syncing is on
+fn sync_official(app_dir: &std::path::Path, manager: &mut ProfileManager) {
+    let settings = SettingsManager::new(app_dir.to_path_buf()).load();
+    if settings.sync_official_profiles {
+        if let Err(e) = manager.export_official(&minecraft_dir(&settings)) {
+            println!("WARN: Could not sync profiles to the official launcher: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+async fn get_mods(version_id: String, profile_id: Option<String>, app_handle: tauri::AppHandle) -> Result<Vec<Mod>, String> {
     use tauri::Manager;
     let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let manager = ModManager::new(app_dir);
-    let mods_dir = manager.get_mods_dir(&version_id);
+    let manager = ModManager::new(app_dir.clone());
+    let mods_dir = match profile_id {
+        Some(id) => profile_mods_dir(&app_dir, &id)?,
+        None => manager.get_mods_dir(&version_id),
+    };
     manager.scan_mods(&mods_dir)
 }
 
@@ -194,10 +438,7 @@ async fn get_global_mods(app_handle: tauri::AppHandle) -> Result<Vec<Mod>, Strin
     let settings_mgr = SettingsManager::new(app_dir.clone());
     let settings = settings_mgr.load();
     
-    let mc_dir = match settings.minecraft_dir {
-        Some(dir) => std::path::PathBuf::from(dir),
-        None => game::scanner::find_minecraft_dir(),
-    };
+    let mc_dir = minecraft_dir(&settings);
     
     let mods_dir = mc_dir.join("mods");
     let manager = ModManager::new(app_dir);
@@ -205,24 +446,33 @@ async fn get_global_mods(app_handle: tauri::AppHandle) -> Result<Vec<Mod>, Strin
 }
 
 #[tauri::command]
-async fn toggle_mod(version_id: String, filename: String, enable: bool, app_handle: tauri::AppHandle) -> Result<(), String> {
+async fn toggle_mod(version_id: String, profile_id: Option<String>, filename: String, enable: bool, app_handle: tauri::AppHandle) -> Result<(), String> {
     use tauri::Manager;
     let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
-    let manager = ModManager::new(app_dir);
-    let mods_dir = manager.get_mods_dir(&version_id);
+    let manager = ModManager::new(app_dir.clone());
+    let mods_dir = match profile_id {
+        Some(id) => profile_mods_dir(&app_dir, &id)?,
+        None => manager.get_mods_dir(&version_id),
+    };
     manager.toggle_mod(&mods_dir, &filename, enable)
 }
 
+/// Mods folder the game reads for a profile, inside its instance directory when it has one
+fn profile_mods_dir(app_dir: &std::path::Path, profile_id: &str) -> Result<std::path::PathBuf, String> {
+    let settings = SettingsManager::new(app_dir.to_path_buf()).load();
+    let mc_dir = minecraft_dir(&settings);
+    let manager = ProfileManager::new(app_dir.to_path_buf());
+    let profile = manager.profiles.get(profile_id).ok_or("Profile not found")?;
+    Ok(profile.mods_dir(&manager.instances_dir(), &mc_dir))
+}
+
 #[tauri::command]
 async fn toggle_global_mod(filename: String, enable: bool, app_handle: tauri::AppHandle) -> Result<(), String> {
     let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
     let settings_mgr = SettingsManager::new(app_dir.clone());
     let settings = settings_mgr.load();
     
-    let mc_dir = match settings.minecraft_dir {
-        Some(dir) => std::path::PathBuf::from(dir),
-        None => game::scanner::find_minecraft_dir(),
-    };
+    let mc_dir = minecraft_dir(&settings);
     
     let mods_dir = mc_dir.join("mods");
     let manager = ModManager::new(app_dir);
@@ -234,7 +484,10 @@ async fn get_java_runtimes(app_handle: tauri::AppHandle) -> Result<Vec<JavaRunti
     use tauri::Manager;
     let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
     let manager = JavaManager::new(app_dir);
-    Ok(manager.detect_system_java())
+    // Probing runs `java` once per candidate; keep it off the async workers
+    tokio::task::spawn_blocking(move || manager.detect_system_java())
+        .await
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -245,6 +498,80 @@ async fn install_java_cmd(major_version: u32, app_handle: tauri::AppHandle) -> R
     manager.ensure_java(major_version, app_handle).await
 }
 
+/// Profile names per managed runtime id, for reference counting
+async fn java_runtime_users(app_dir: &std::path::Path, manager: &JavaManager) -> std::collections::HashMap<String, Vec<String>> {
+    let settings = SettingsManager::new(app_dir.to_path_buf()).load();
+    let mc_dir = minecraft_dir(&settings);
+    let game_manager = game::GameManager::new(mc_dir);
+
+    let mut users: std::collections::HashMap<String, Vec<String>> = std::collections::HashMap::new();
+    for profile in ProfileManager::new(app_dir.to_path_buf()).list_profiles() {
+        let runtime_id = match &profile.java_path {
+            Some(path) => manager.runtime_id_for_path(path),
+            None => match game_manager.load_manifest(&profile.version_id).await {
+                Ok(manifest) => {
+                    let major = JavaManager::required_java_version(&manifest);
+                    let component = manifest.java_version.as_ref().and_then(|j| j.component.as_deref());
+                    let provider = profile.java_provider.unwrap_or(settings.java_provider);
+                    Some(JavaManager::runtime_id(major, component, provider))
+                }
+                Err(_) => None,
+            },
+        };
+        if let Some(id) = runtime_id {
+            users.entry(id).or_default().push(profile.name.clone());
+        }
+    }
+    users
+}
+
+#[tauri::command]
+async fn list_java_runtimes(app_handle: tauri::AppHandle) -> Result<Vec<java::runtimes::ManagedRuntime>, String> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let manager = JavaManager::new(app_dir.clone());
+    let mut users = java_runtime_users(&app_dir, &manager).await;
+    let mut runtimes = manager.list_managed_runtimes();
+    for runtime in &mut runtimes {
+        runtime.used_by = users.remove(&runtime.id).unwrap_or_default();
+    }
+    Ok(runtimes)
+}
+
+#[tauri::command]
+async fn check_java_updates(app_handle: tauri::AppHandle) -> Result<Vec<java::runtimes::RuntimeUpdate>, String> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let manager = JavaManager::new(app_dir);
+    let mut updates = Vec::new();
+    for runtime in manager.list_managed_runtimes() {
+        match manager.check_runtime_update(&runtime.id).await {
+            Ok(update) => updates.push(update),
+            Err(e) => println!("WARN: Could not check Java runtime {} for updates: {}", runtime.id, e),
+        }
+    }
+    Ok(updates)
+}
+
+#[tauri::command]
+async fn verify_java_runtime(id: String, app_handle: tauri::AppHandle) -> Result<java::runtimes::RuntimeCheck, String> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    JavaManager::new(app_dir).verify_runtime(&id).await
+}
+
+#[tauri::command]
+async fn update_java_runtime(id: String, app_handle: tauri::AppHandle) -> Result<String, String> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    JavaManager::new(app_dir).update_runtime(&id, app_handle).await
+}
+
+#[tauri::command]
+async fn uninstall_java_runtime(id: String, force: Option<bool>, app_handle: tauri::AppHandle) -> Result<(), String> {
+    let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let manager = JavaManager::new(app_dir.clone());
+    let users = java_runtime_users(&app_dir, &manager).await;
+    let used_by = users.get(&id).cloned().unwrap_or_default();
+    manager.uninstall_runtime(&id, &used_by, force.unwrap_or(false))
+}
+
 #[tauri::command]
 async fn get_settings(app_handle: tauri::AppHandle) -> Result<Settings, String> {
     let app_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
@@ -265,10 +592,7 @@ async fn get_local_versions(app_handle: tauri::AppHandle) -> Result<Vec<game::sc
     let settings_mgr = SettingsManager::new(app_dir.clone());
     let settings = settings_mgr.load();
     
-    let mc_dir = match settings.minecraft_dir {
-        Some(dir) => std::path::PathBuf::from(dir),
-        None => game::scanner::find_minecraft_dir(),
-    };
+    let mc_dir = minecraft_dir(&settings);
     
     Ok(game::scanner::scan_versions(&mc_dir))
 }
@@ -319,6 +643,7 @@ pub fn run() {
     let app_state = AppState {
         current_profile: Arc::new(Mutex::new(None)),
         active_mods: Arc::new(Mutex::new(Vec::new())),
+        supervisor: game::supervisor::GameSupervisor::new(),
     };
 
     let api_state = app_state.clone();
@@ -333,21 +658,39 @@ pub fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
         .manage(app_state) 
+        .setup(|app| {
+            tauri::async_runtime::spawn(sync_official_on_start(app.handle().clone()));
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             start_ms_auth,
             complete_ms_auth,
             login_offline,
             install_game,
+            repair_game,
             launch_game_cmd,
+            launch_profile,
+            launch_into_server,
+            kill_game,
+            list_running,
+            list_game_logs,
+            read_game_log,
             refresh_ms_token,
             get_profiles,
             create_profile,
             update_profile,
             delete_profile,
+            import_official_profiles,
+            export_official_profiles,
             get_mods,
             toggle_mod,
             get_java_runtimes,
             install_java_cmd,
+            list_java_runtimes,
+            check_java_updates,
+            verify_java_runtime,
+            update_java_runtime,
+            uninstall_java_runtime,
             get_settings,
             save_settings,
             get_local_versions,
@@ -7,6 +7,7 @@ use zip::ZipArchive;
 
 #[derive(Debug, Clone)]
 pub struct ModMetadata {
+    pub mod_id: Option<String>,
     pub name: Option<String>,
     pub version: Option<String>,
     pub game_version: Option<String>,
@@ -17,6 +18,7 @@ pub struct ModMetadata {
 impl Default for ModMetadata {
     fn default() -> Self {
         Self {
+            mod_id: None,
             name: None,
             version: None,
             game_version: None,
@@ -29,6 +31,7 @@ impl Default for ModMetadata {
 // Fabric JSON struct
 #[derive(Deserialize)]
 struct FabricModJson {
+    id: Option<String>,
     name: Option<String>,
     version: Option<String>,
     description: Option<String>,
@@ -45,6 +48,8 @@ struct ModsToml {
 
 #[derive(Deserialize)]
 struct ModsTomlEntry {
+    #[serde(rename = "modId")]
+    mod_id: Option<String>,
     displayName: Option<String>,
     version: Option<String>,
     description: Option<String>,
@@ -54,6 +59,7 @@ struct ModsTomlEntry {
 // Forge/Legacy mcmod.info struct (1.12-)
 #[derive(Deserialize)]
 struct McModInfoEntry {
+    modid: Option<String>,
     name: Option<String>,
     version: Option<String>,
     mcversion: Option<String>,
@@ -83,6 +89,7 @@ pub fn read_metadata(path: &Path) -> ModMetadata {
                     .and_then(|d| d.get("minecraft").cloned());
 
                 return ModMetadata {
+                    mod_id: json.id,
                     name: json.name,
                     version: json.version,
                     game_version,
@@ -101,6 +108,7 @@ pub fn read_metadata(path: &Path) -> ModMetadata {
                 if let Some(mods) = toml.mods {
                     if let Some(first) = mods.first() {
                         return ModMetadata {
+                            mod_id: first.mod_id.clone(),
                             name: first.displayName.clone(),
                             version: first.version.clone(),
                             game_version: None, // Harder to get from TOML without deep parsing dependencies
@@ -121,6 +129,7 @@ pub fn read_metadata(path: &Path) -> ModMetadata {
             if let Ok(list) = serde_json::from_str::<Vec<McModInfoEntry>>(&content) {
                 if let Some(first) = list.first() {
                     return ModMetadata {
+                        mod_id: first.modid.clone(),
                         name: first.name.clone(),
                         version: first.version.clone(),
                         game_version: first.mcversion.clone(),
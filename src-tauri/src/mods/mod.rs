@@ -7,6 +7,9 @@ use metadata::read_metadata;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Mod {
+    /// Loader mod id from fabric.mod.json, mods.toml or mcmod.info
+    #[serde(default)]
+    pub mod_id: Option<String>,
     pub name: String,
     pub filename: String,
     pub path: String,
@@ -63,6 +66,7 @@ impl ModManager {
                     }
 
                     mods.push(Mod {
+                        mod_id: meta.mod_id,
                         name,
                         filename: filename.clone(),
                         path: path.to_string_lossy().to_string(),
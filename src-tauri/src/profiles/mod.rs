@@ -1,8 +1,13 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
+use crate::game::jvm_args::JvmPreset;
+use crate::game::launcher::QuickPlay;
+use crate::java::JavaProvider;
+
+pub mod official;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
@@ -10,6 +15,9 @@ pub struct Profile {
     pub name: String,
     pub version_id: String,
     pub java_args: String,
+    /// Tuning preset applied before `java_args`
+    #[serde(default)]
+    pub jvm_preset: Option<JvmPreset>,
     pub java_path: Option<String>,
     pub min_memory: u32,
     pub max_memory: u32,
@@ -17,9 +25,60 @@ pub struct Profile {
     pub height: u32,
     #[serde(default)]
     pub enabled_mods: Vec<String>,
+    /// Overrides `Settings.java_provider` for this profile
+    #[serde(default)]
+    pub java_provider: Option<JavaProvider>,
+    /// Use a managed runtime instead when `java_path` fails the preflight check
+    #[serde(default)]
+    pub java_fallback: bool,
+    /// Instance directory with its own mods, config, saves and options; relative paths live
+    /// under the launcher's `instances/`, `None` shares the .minecraft directory
+    #[serde(default)]
+    pub game_dir: Option<String>,
+    /// Overrides `Settings.full_screen` for this profile
+    #[serde(default)]
+    pub full_screen: Option<bool>,
+    #[serde(default)]
+    pub demo: bool,
+    /// World, server or realm to join on every launch
+    #[serde(default)]
+    pub quick_play: Option<QuickPlay>,
+    /// Icon name or data URL, as in the official launcher
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Key of the linked entry in the official launcher's `launcher_profiles.json`
+    #[serde(default)]
+    pub official_id: Option<String>,
+    /// `type` of the linked entry when it was imported, so exporting keeps `latest-*` profiles
+    #[serde(default)]
+    pub official_type: Option<String>,
+    /// The profile made on first start; it isn't exported until the user edits it
+    #[serde(default)]
+    pub auto_created: bool,
     pub created_at: i64,
 }
 
+/// Per-instance folders; libraries, assets and versions stay in the shared .minecraft
+const INSTANCE_DIRS: &[&str] = &["mods", "config", "saves", "resourcepacks", "shaderpacks"];
+
+impl Profile {
+    /// Directory the game runs in: `--gameDir`, working directory, crash reports
+    pub fn game_dir(&self, instances_dir: &Path, mc_dir: &Path) -> PathBuf {
+        match &self.game_dir {
+            Some(dir) if Path::new(dir).is_absolute() => PathBuf::from(dir),
+            Some(dir) => instances_dir.join(dir),
+            None => mc_dir.to_path_buf(),
+        }
+    }
+
+    pub fn mods_dir(&self, instances_dir: &Path, mc_dir: &Path) -> PathBuf {
+        match &self.game_dir {
+            Some(_) => self.game_dir(instances_dir, mc_dir).join("mods"),
+            None => mc_dir.join("versions").join(&self.version_id).join("mods"),
+        }
+    }
+}
+
 impl Default for Profile {
     fn default() -> Self {
         Self {
@@ -27,12 +86,23 @@ impl Default for Profile {
             name: "Default Profile".to_string(),
             version_id: "1.19.4".to_string(),
             java_args: "-XX:+UseG1GC".to_string(),
+            jvm_preset: None,
             java_path: None,
             min_memory: 1024,
             max_memory: 4096,
             width: 854,
             height: 480,
             enabled_mods: Vec::new(),
+            java_provider: None,
+            java_fallback: false,
+            game_dir: None,
+            full_screen: None,
+            demo: false,
+            quick_play: None,
+            icon: None,
+            official_id: None,
+            official_type: None,
+            auto_created: false,
             created_at: chrono::Utc::now().timestamp(),
         }
     }
@@ -53,6 +123,31 @@ impl ProfileManager {
         manager
     }
 
+    pub fn instances_dir(&self) -> PathBuf {
+        self.base_dir.join("instances")
+    }
+
+    /// Create the profile's instance directory on first use, seeding options.txt from the
+    /// shared directory so key binds and video settings carry over
+    pub fn prepare_game_dir(&self, profile: &Profile, mc_dir: &Path) -> Result<PathBuf, String> {
+        let game_dir = profile.game_dir(&self.instances_dir(), mc_dir);
+        if profile.game_dir.is_none() || game_dir.exists() {
+            return Ok(game_dir);
+        }
+
+        println!("DEBUG: Creating instance directory {:?} for {}", game_dir, profile.name);
+        for dir in INSTANCE_DIRS {
+            fs::create_dir_all(game_dir.join(dir)).map_err(|e| e.to_string())?;
+        }
+        let options = mc_dir.join("options.txt");
+        if options.exists() {
+            if let Err(e) = fs::copy(&options, game_dir.join("options.txt")) {
+                println!("WARN: Could not copy options.txt into {:?}: {}", game_dir, e);
+            }
+        }
+        Ok(game_dir)
+    }
+
     fn get_file_path(&self) -> PathBuf {
         self.base_dir.join("profiles.json")
     }
@@ -61,7 +156,7 @@ impl ProfileManager {
         let path = self.get_file_path();
         if !path.exists() {
             // Create default if not exists
-            let default_profile = Profile::default();
+            let default_profile = Profile { auto_created: true, ..Profile::default() };
             self.profiles
                 .insert(default_profile.id.clone(), default_profile);
             return self.save();
@@ -90,8 +185,12 @@ impl ProfileManager {
         profile
     }
 
-    pub fn update_profile(&mut self, profile: Profile) -> Result<(), String> {
+    pub fn update_profile(&mut self, mut profile: Profile) -> Result<(), String> {
+        if let Some(quick_play) = &profile.quick_play {
+            quick_play.validate()?;
+        }
         if self.profiles.contains_key(&profile.id) {
+            profile.auto_created = false;
             self.profiles.insert(profile.id.clone(), profile);
             self.save()
         } else {
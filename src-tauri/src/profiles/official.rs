@@ -0,0 +1,342 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::game::installer::LatestVersions;
+use crate::game::jvm_args::parse_java_args;
+use crate::profiles::{Profile, ProfileManager};
+
+/// `launcher_profiles.json` as written by the official launcher; unknown fields are kept so
+/// exporting doesn't drop the launcher's own settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LauncherProfiles {
+    #[serde(default)]
+    pub profiles: HashMap<String, OfficialProfile>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OfficialProfile {
+    #[serde(default)]
+    pub name: String,
+    /// `custom`, `latest-release` or `latest-snapshot`
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub profile_type: Option<String>,
+    #[serde(rename = "lastVersionId", default, skip_serializing_if = "Option::is_none")]
+    pub last_version_id: Option<String>,
+    #[serde(rename = "gameDir", default, skip_serializing_if = "Option::is_none")]
+    pub game_dir: Option<String>,
+    /// Path to the java executable, despite the name
+    #[serde(rename = "javaDir", default, skip_serializing_if = "Option::is_none")]
+    pub java_dir: Option<String>,
+    #[serde(rename = "javaArgs", default, skip_serializing_if = "Option::is_none")]
+    pub java_args: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<Resolution>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    #[serde(flatten)]
+    pub other: serde_json::Map<String, serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl OfficialProfile {
+    /// `latest-*` profiles have no fixed version and need the version list to resolve
+    pub fn is_latest(&self) -> bool {
+        matches!(self.profile_type.as_deref(), Some("latest-release" | "latest-snapshot"))
+    }
+}
+
+pub fn launcher_profiles_path(mc_dir: &Path) -> PathBuf {
+    mc_dir.join("launcher_profiles.json")
+}
+
+pub fn read_launcher_profiles(mc_dir: &Path) -> Result<LauncherProfiles, String> {
+    let content = fs::read_to_string(launcher_profiles_path(mc_dir)).map_err(|e| e.to_string())?;
+    parse_launcher_profiles(&content)
+}
+
+fn parse_launcher_profiles(content: &str) -> Result<LauncherProfiles, String> {
+    serde_json::from_str(content).map_err(|e| format!("Failed to parse launcher_profiles.json: {}", e))
+}
+
+fn write_launcher_profiles(mc_dir: &Path, profiles: &LauncherProfiles) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(profiles).map_err(|e| e.to_string())?;
+    fs::write(launcher_profiles_path(mc_dir), content).map_err(|e| e.to_string())
+}
+
+impl ProfileManager {
+    /// Import or refresh profiles from the official launcher. Profiles imported before are
+    /// updated in place, keeping the settings only this launcher has, and linked profiles whose
+    /// entry was deleted there are deleted here too.
+    pub fn import_official(&mut self, mc_dir: &Path, latest: Option<&LatestVersions>) -> Result<Vec<Profile>, String> {
+        let official = read_launcher_profiles(mc_dir)?;
+        let mut imported = Vec::new();
+
+        for (key, entry) in &official.profiles {
+            let version_id = match (entry.profile_type.as_deref(), latest) {
+                (Some("latest-release"), Some(latest)) => latest.release.clone(),
+                (Some("latest-snapshot"), Some(latest)) => latest.snapshot.clone(),
+                _ => match &entry.last_version_id {
+                    Some(id) if !entry.is_latest() => id.clone(),
+                    _ => {
+                        println!("WARN: Skipping official profile {}, its version can't be resolved", key);
+                        continue;
+                    }
+                },
+            };
+
+            let existing = self.profiles.values().find(|p| p.official_id.as_deref() == Some(key.as_str())).cloned();
+            let mut profile = existing.unwrap_or_else(|| Profile {
+                official_id: Some(key.clone()),
+                created_at: entry
+                    .created
+                    .as_deref()
+                    .and_then(|c| chrono::DateTime::parse_from_rfc3339(c).ok())
+                    .map(|c| c.timestamp())
+                    .unwrap_or_else(|| chrono::Utc::now().timestamp()),
+                ..Profile::default()
+            });
+
+            profile.official_type = entry.profile_type.clone();
+            profile.name = if entry.name.is_empty() { version_id.clone() } else { entry.name.clone() };
+            profile.version_id = version_id;
+            profile.game_dir = entry.game_dir.clone();
+            profile.java_path = entry.java_dir.clone();
+            profile.icon = entry.icon.clone();
+            if let Some(resolution) = &entry.resolution {
+                profile.width = resolution.width;
+                profile.height = resolution.height;
+            }
+            if let Some(java_args) = &entry.java_args {
+                let (args, min_memory, max_memory) = split_memory_args(java_args);
+                profile.java_args = args;
+                profile.min_memory = min_memory.unwrap_or(profile.min_memory);
+                profile.max_memory = max_memory.unwrap_or(profile.max_memory);
+            }
+
+            self.profiles.insert(profile.id.clone(), profile.clone());
+            imported.push(profile);
+        }
+
+        let deleted: Vec<String> = self
+            .profiles
+            .values()
+            .filter(|p| p.official_id.as_ref().is_some_and(|key| !official.profiles.contains_key(key)))
+            .map(|p| p.id.clone())
+            .collect();
+        for id in &deleted {
+            self.profiles.remove(id);
+        }
+
+        println!("DEBUG: Imported {} profiles from launcher_profiles.json, removed {}", imported.len(), deleted.len());
+        self.save()?;
+        Ok(imported)
+    }
+
+    /// Write the user's profiles into `launcher_profiles.json`, linking new ones so later syncs
+    /// update the same entry. The untouched profile made on first start is left out.
+    pub fn export_official(&mut self, mc_dir: &Path) -> Result<usize, String> {
+        // Only a missing file starts empty; overwriting one we failed to parse would lose the
+        // official launcher's other data
+        let mut official = match fs::read_to_string(launcher_profiles_path(mc_dir)) {
+            Ok(content) => parse_launcher_profiles(&content)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => LauncherProfiles::default(),
+            Err(e) => return Err(e.to_string()),
+        };
+        let instances_dir = self.instances_dir();
+
+        let mut count = 0;
+        for profile in self.profiles.values_mut().filter(|p| !p.auto_created || p.official_id.is_some()) {
+            count += 1;
+            let key = profile.official_id.get_or_insert_with(|| profile.id.replace('-', "")).clone();
+            let entry = official.profiles.entry(key).or_default();
+
+            entry.name = profile.name.clone();
+            // `latest-*` entries follow the newest version, pinning them would break that
+            let profile_type = profile.official_type.clone().or_else(|| entry.profile_type.clone());
+            entry.profile_type = Some(profile_type.unwrap_or_else(|| "custom".to_string()));
+            if !entry.is_latest() {
+                entry.last_version_id = Some(profile.version_id.clone());
+            }
+            entry.game_dir = profile.game_dir.as_ref().map(|_| profile.game_dir(&instances_dir, mc_dir).to_string_lossy().to_string());
+            entry.java_dir = profile.java_path.clone();
+            entry.java_args = Some(format!("-Xmx{}M -Xms{}M {}", profile.max_memory, profile.min_memory, profile.java_args).trim_end().to_string());
+            entry.resolution = Some(Resolution { width: profile.width, height: profile.height });
+            entry.icon = Some(profile.icon.clone().unwrap_or_else(|| "Furnace".to_string()));
+            if entry.created.is_none() {
+                entry.created = chrono::DateTime::from_timestamp(profile.created_at, 0).map(|c| c.to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+            }
+        }
+
+        write_launcher_profiles(mc_dir, &official)?;
+        self.save()?;
+        Ok(count)
+    }
+
+    /// Drop an exported profile from `launcher_profiles.json` so a sync doesn't bring it back
+    pub fn remove_official(&self, mc_dir: &Path, official_id: &str) -> Result<(), String> {
+        let mut official = read_launcher_profiles(mc_dir)?;
+        if official.profiles.remove(official_id).is_some() {
+            write_launcher_profiles(mc_dir, &official)?;
+        }
+        Ok(())
+    }
+}
+
+/// Pull `-Xms`/`-Xmx` out of the official launcher's `javaArgs`, returning the rest and the sizes in MB
+fn split_memory_args(java_args: &str) -> (String, Option<u32>, Option<u32>) {
+    let Ok(args) = parse_java_args(java_args) else {
+        return (java_args.to_string(), None, None);
+    };
+
+    let (mut min_memory, mut max_memory) = (None, None);
+    let mut rest = Vec::new();
+    for arg in args {
+        if let Some(size) = arg.strip_prefix("-Xms").and_then(memory_mb) {
+            min_memory = Some(size);
+        } else if let Some(size) = arg.strip_prefix("-Xmx").and_then(memory_mb) {
+            max_memory = Some(size);
+        } else {
+            rest.push(arg);
+        }
+    }
+    let rest = shlex::try_join(rest.iter().map(String::as_str)).unwrap_or_else(|_| rest.join(" "));
+    (rest, min_memory, max_memory)
+}
+
+/// `2G`, `2048M`, `2097152K` or plain bytes to megabytes
+fn memory_mb(size: &str) -> Option<u32> {
+    let size = size.to_ascii_lowercase();
+    let (number, factor) = match size.chars().last()? {
+        'g' => (&size[..size.len() - 1], 1024.0),
+        'm' => (&size[..size.len() - 1], 1.0),
+        'k' => (&size[..size.len() - 1], 1.0 / 1024.0),
+        _ => (size.as_str(), 1.0 / (1024.0 * 1024.0)),
+    };
+    let mb = number.parse::<f64>().ok()? * factor;
+    (mb >= 1.0).then_some(mb as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("jugelauncher-test-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn converts_memory_sizes() {
+        assert_eq!(memory_mb("2G"), Some(2048));
+        assert_eq!(memory_mb("512m"), Some(512));
+        assert_eq!(memory_mb("2097152K"), Some(2048));
+        assert_eq!(memory_mb("1073741824"), Some(1024));
+        assert_eq!(memory_mb("512k"), None);
+        assert_eq!(memory_mb("lots"), None);
+    }
+
+    #[test]
+    fn splits_memory_from_java_args() {
+        assert_eq!(
+            split_memory_args("-Xmx2G -XX:+UnlockExperimentalVMOptions -Xms512M -XX:+UseG1GC"),
+            ("-XX:+UnlockExperimentalVMOptions -XX:+UseG1GC".to_string(), Some(512), Some(2048))
+        );
+        assert_eq!(split_memory_args("-XX:+UseG1GC"), ("-XX:+UseG1GC".to_string(), None, None));
+        assert_eq!(split_memory_args("-Xmx4G"), (String::new(), None, Some(4096)));
+    }
+
+    #[test]
+    fn imports_and_exports_profiles() {
+        let app_dir = temp_dir();
+        let mc_dir = temp_dir();
+        fs::write(
+            launcher_profiles_path(&mc_dir),
+            json!({
+                "profiles": {
+                    "abc123": {
+                        "name": "Modded",
+                        "type": "custom",
+                        "lastVersionId": "1.20.1-forge-47.2.0",
+                        "gameDir": "/games/modded",
+                        "javaDir": "/opt/java/bin/java",
+                        "javaArgs": "-Xmx6G -XX:+UseG1GC -Xms1G",
+                        "resolution": { "width": 1280, "height": 720 },
+                        "icon": "Grass",
+                        "created": "2023-05-01T12:00:00.000Z",
+                        "lastUsed": "2024-01-01T00:00:00.000Z"
+                    },
+                    "latest": { "name": "", "type": "latest-release" }
+                },
+                "settings": { "locale": "en-us" },
+                "version": 3
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let mut manager = ProfileManager::new(app_dir.clone());
+        let latest = LatestVersions { release: "1.21.1".to_string(), snapshot: "24w40a".to_string() };
+        let imported = manager.import_official(&mc_dir, Some(&latest)).unwrap();
+        assert_eq!(imported.len(), 2);
+
+        let modded = imported.iter().find(|p| p.official_id.as_deref() == Some("abc123")).unwrap();
+        assert_eq!(modded.name, "Modded");
+        assert_eq!(modded.version_id, "1.20.1-forge-47.2.0");
+        assert_eq!(modded.game_dir.as_deref(), Some("/games/modded"));
+        assert_eq!(modded.java_path.as_deref(), Some("/opt/java/bin/java"));
+        assert_eq!(modded.java_args, "-XX:+UseG1GC");
+        assert_eq!((modded.min_memory, modded.max_memory), (1024, 6144));
+        assert_eq!((modded.width, modded.height), (1280, 720));
+        assert_eq!(modded.icon.as_deref(), Some("Grass"));
+        assert_eq!(modded.created_at, 1682942400);
+        let latest_profile = imported.iter().find(|p| p.official_id.as_deref() == Some("latest")).unwrap();
+        assert_eq!((latest_profile.name.as_str(), latest_profile.version_id.as_str()), ("1.21.1", "1.21.1"));
+
+        // The profile made on first start stays out, a new one is linked
+        let created = manager.create_profile("Vanilla".to_string(), "1.8.9".to_string());
+        assert_eq!(manager.export_official(&mc_dir).unwrap(), 3);
+
+        let official = read_launcher_profiles(&mc_dir).unwrap();
+        assert_eq!(official.profiles.len(), 3);
+        assert_eq!(official.other["version"], 3);
+        let entry = &official.profiles["abc123"];
+        assert_eq!(entry.java_args.as_deref(), Some("-Xmx6144M -Xms1024M -XX:+UseG1GC"));
+        assert_eq!(entry.created.as_deref(), Some("2023-05-01T12:00:00.000Z"));
+        assert_eq!(entry.other["lastUsed"], "2024-01-01T00:00:00.000Z");
+        let latest_entry = &official.profiles["latest"];
+        assert_eq!(latest_entry.profile_type.as_deref(), Some("latest-release"));
+        assert_eq!(latest_entry.last_version_id, None);
+        let new_key = manager.profiles[&created.id].official_id.clone().unwrap();
+        assert_eq!(official.profiles[&new_key].last_version_id.as_deref(), Some("1.8.9"));
+        assert_eq!(official.profiles[&new_key].profile_type.as_deref(), Some("custom"));
+
+        // Deleting an entry in the official launcher deletes the linked profile
+        remove_entry(&mc_dir, "abc123");
+        manager.import_official(&mc_dir, Some(&latest)).unwrap();
+        assert!(manager.profiles.values().all(|p| p.official_id.as_deref() != Some("abc123")));
+        assert!(manager.profiles.contains_key(&created.id));
+        assert!(manager.profiles.values().any(|p| p.auto_created));
+
+        let _ = fs::remove_dir_all(app_dir);
+        let _ = fs::remove_dir_all(mc_dir);
+    }
+
+    fn remove_entry(mc_dir: &Path, key: &str) {
+        let mut official = read_launcher_profiles(mc_dir).unwrap();
+        official.profiles.remove(key);
+        write_launcher_profiles(mc_dir, &official).unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use crate::java::JavaProvider;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
@@ -13,6 +14,17 @@ pub struct Settings {
     pub java_path: Option<String>,
     pub theme: String,
     pub language: String,
+    #[serde(default = "default_download_concurrency")]
+    pub download_concurrency: usize,
+    #[serde(default)]
+    pub java_provider: JavaProvider,
+    /// Keep profiles in sync with the official launcher's `launcher_profiles.json`
+    #[serde(default)]
+    pub sync_official_profiles: bool,
+}
+
+fn default_download_concurrency() -> usize {
+    crate::game::downloader::DEFAULT_CONCURRENCY
 }
 
 impl Default for Settings {
@@ -27,6 +39,9 @@ impl Default for Settings {
             java_path: None,
             theme: "dark".to_string(),
             language: "en".to_string(),
+            download_concurrency: default_download_concurrency(),
+            java_provider: JavaProvider::default(),
+            sync_official_profiles: false,
         }
     }
 }
@@ -76,10 +76,7 @@ function App() {
     setStatus(t('errors.installing_for', { name: activeProfile.name }));
     setLoading(true);
     try {
-      const version = activeProfile.version_id;
-      const manifestUrl = `https://raw.githubusercontent.com/jugecraft/JugeLauncher2/main/versions/${version}/manifest.json`;
-
-      const msg = await invoke('install_game', { manifestUrl });
+      const msg = await invoke('install_game', { versionId: activeProfile.version_id });
       setStatus(msg as string);
     } catch (e: any) {
       setStatus(t('errors.error_generic', { error: e.toString() }));
@@ -94,7 +91,7 @@ function App() {
     try {
       setGameLogs([]); // Clear previous logs
       setShowConsole(true); // Show console immediately
-      await invoke('launch_game_cmd', { manifestId: activeProfile.version_id, account: account });
+      await invoke('launch_profile', { profileId: activeProfile.id, account: account });
       setStatus(t('dashboard.game_running'));
     } catch (e: any) { setStatus(t('errors.launch_error', { error: e.toString() })); }
     finally { setLoading(false); }
@@ -216,7 +213,7 @@ function App() {
 
               <div className="flex-1 glass-panel p-6 rounded-[2rem] border-white/5 overflow-hidden">
                 {activeProfile ? (
-                  <ModManager versionId={activeProfile.version_id} />
+                  <ModManager versionId={activeProfile.version_id} profileId={activeProfile.id} />
                 ) : (
                   <div className="space-y-6">
                     <div className="p-4 bg-accent-primary/5 border border-accent-primary/20 rounded-2xl text-xs font-bold text-accent-primary flex items-center gap-2">
@@ -26,7 +26,18 @@ export const LoginScreen: React.FC<LoginScreenProps> = ({ onLoginSuccess }) => {
 
     useEffect(() => {
         if (msAccount) {
-            onLoginSuccess(msAccount);
+            // Same shape as the backend's Account, which launch_profile expects
+            onLoginSuccess({
+                uuid: msAccount.uuid,
+                name: msAccount.username,
+                access_token: msAccount.mcAccessToken,
+                refresh_token: msAccount.refreshToken,
+                account_type: 'Microsoft',
+                skin_url: null,
+                cape_url: null,
+                expires_at: msAccount.expiresAt,
+                xuid: msAccount.xuid ?? null,
+            });
         }
     }, [msAccount, onLoginSuccess]);
 
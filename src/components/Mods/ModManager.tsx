@@ -18,9 +18,10 @@ interface Mod {
 
 interface ModManagerProps {
     versionId?: string;
+    profileId?: string;
 }
 
-export const ModManager: React.FC<ModManagerProps> = ({ versionId }) => {
+export const ModManager: React.FC<ModManagerProps> = ({ versionId, profileId }) => {
     const { t } = useLanguage();
     const [mods, setMods] = useState<Mod[]>([]);
     const [loading, setLoading] = useState(true);
@@ -29,13 +30,13 @@ export const ModManager: React.FC<ModManagerProps> = ({ versionId }) => {
 
     useEffect(() => {
         loadMods();
-    }, [versionId]);
+    }, [versionId, profileId]);
 
     const loadMods = async () => {
         setLoading(true);
         try {
             const result: Mod[] = versionId
-                ? await invoke('get_mods', { versionId })
+                ? await invoke('get_mods', { versionId, profileId })
                 : await invoke('get_global_mods');
             setMods(result);
         } catch (e) {
@@ -49,7 +50,7 @@ export const ModManager: React.FC<ModManagerProps> = ({ versionId }) => {
         e.stopPropagation();
         try {
             if (versionId) {
-                await invoke('toggle_mod', { versionId, filename: mod.filename, enable: !mod.enabled });
+                await invoke('toggle_mod', { versionId, profileId, filename: mod.filename, enable: !mod.enabled });
             } else {
                 await invoke('toggle_global_mod', { filename: mod.filename, enable: !mod.enabled });
             }
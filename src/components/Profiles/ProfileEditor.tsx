@@ -11,9 +11,21 @@ interface Profile {
     width: number;
     height: number;
     java_path?: string;
+    java_fallback?: boolean;
     java_args: string;
+    java_provider?: 'adoptium' | 'mojang' | null;
+    jvm_preset?: string | null;
+    game_dir?: string | null;
+    full_screen?: boolean | null;
+    demo?: boolean;
+    quick_play?: QuickPlay | null;
 }
 
+type QuickPlay =
+    | { type: 'singleplayer'; world: string }
+    | { type: 'multiplayer'; host: string; port?: number | null }
+    | { type: 'realm'; realm_id: string };
+
 interface ProfileEditorProps {
     profile: Profile | null; // Null means creating new
     onClose: () => void;
@@ -128,12 +140,34 @@ export const ProfileEditor: React.FC<ProfileEditorProps> = ({ profile, onClose,
                             />
                             <datalist id="java-list">
                                 {javaRuntimes.map((java, i) => (
-                                    <option key={i} value={java.path}>{java.version} ({java.architecture})</option>
+                                    <option key={i} value={java.path}>Java {java.major_version} - {java.version} {java.vendor} ({java.architecture})</option>
                                 ))}
                             </datalist>
+                            <label className="flex items-center gap-2 mt-2 text-xs text-gray-400">
+                                <input
+                                    type="checkbox"
+                                    checked={!!formData.java_fallback}
+                                    disabled={!formData.java_path}
+                                    onChange={e => setFormData({ ...formData, java_fallback: e.target.checked })}
+                                />
+                                Use a managed runtime if this Java can't run the version
+                            </label>
                         </div>
                     </div>
 
+                    <div>
+                        <label className="block text-sm font-medium text-gray-400 mb-1">Managed Java Runtime</label>
+                        <select
+                            value={formData.java_provider || ''}
+                            onChange={e => setFormData({ ...formData, java_provider: (e.target.value || null) as Profile['java_provider'] })}
+                            className="w-full px-4 py-2 bg-black/30 border border-white/10 rounded-xl text-white focus:border-indigo-500 outline-none"
+                        >
+                            <option value="">Use Settings default</option>
+                            <option value="adoptium">Adoptium (Temurin)</option>
+                            <option value="mojang">Mojang (official)</option>
+                        </select>
+                    </div>
+
                     <div className="bg-white/5 p-4 rounded-xl space-y-4">
                         <h3 className="text-sm font-bold text-gray-300 uppercase tracking-wider">Memory Settings</h3>
                         <div className="grid grid-cols-2 gap-4">
@@ -158,6 +192,126 @@ export const ProfileEditor: React.FC<ProfileEditorProps> = ({ profile, onClose,
                         </div>
                     </div>
 
+                    <div className="bg-white/5 p-4 rounded-xl space-y-3">
+                        <label className="flex items-center gap-2 text-sm text-gray-300">
+                            <input
+                                type="checkbox"
+                                checked={!!formData.game_dir}
+                                disabled={!profile}
+                                onChange={e => setFormData({ ...formData, game_dir: e.target.checked ? profile?.id : null })}
+                            />
+                            Separate game directory (own mods, configs, saves and options)
+                        </label>
+                        {formData.game_dir && (
+                            <input
+                                type="text"
+                                value={formData.game_dir}
+                                onChange={e => setFormData({ ...formData, game_dir: e.target.value || null })}
+                                className="w-full px-3 py-2 bg-black/30 border border-white/10 rounded-lg text-white font-mono text-xs"
+                                placeholder="Folder under instances/ or an absolute path"
+                            />
+                        )}
+                    </div>
+
+                    <div className="bg-white/5 p-4 rounded-xl space-y-3">
+                        <h3 className="text-sm font-bold text-gray-300 uppercase tracking-wider">Launch</h3>
+                        <div className="flex items-center gap-6">
+                            <label className="flex items-center gap-2 text-sm text-gray-300">
+                                Fullscreen
+                                <select
+                                    value={formData.full_screen == null ? '' : String(formData.full_screen)}
+                                    onChange={e => setFormData({ ...formData, full_screen: e.target.value === '' ? null : e.target.value === 'true' })}
+                                    className="px-3 py-1 bg-black/30 border border-white/10 rounded-lg text-white"
+                                >
+                                    <option value="">Use Settings default</option>
+                                    <option value="true">On</option>
+                                    <option value="false">Off</option>
+                                </select>
+                            </label>
+                            <label className="flex items-center gap-2 text-sm text-gray-300">
+                                <input
+                                    type="checkbox"
+                                    checked={!!formData.demo}
+                                    onChange={e => setFormData({ ...formData, demo: e.target.checked })}
+                                />
+                                Demo mode
+                            </label>
+                        </div>
+                        <div>
+                            <label className="block text-xs text-gray-500 mb-1">Join on launch</label>
+                            <div className="grid grid-cols-3 gap-2">
+                                <select
+                                    value={formData.quick_play?.type || ''}
+                                    onChange={e => {
+                                        const type = e.target.value;
+                                        const quickPlay: QuickPlay | null =
+                                            type === 'singleplayer' ? { type, world: '' } :
+                                            type === 'multiplayer' ? { type, host: '', port: null } :
+                                            type === 'realm' ? { type, realm_id: '' } : null;
+                                        setFormData({ ...formData, quick_play: quickPlay });
+                                    }}
+                                    className="px-3 py-2 bg-black/30 border border-white/10 rounded-lg text-white"
+                                >
+                                    <option value="">Nothing</option>
+                                    <option value="singleplayer">World</option>
+                                    <option value="multiplayer">Server</option>
+                                    <option value="realm">Realm</option>
+                                </select>
+                                {formData.quick_play?.type === 'singleplayer' && (
+                                    <input
+                                        type="text"
+                                        placeholder="World folder"
+                                        value={formData.quick_play.world}
+                                        onChange={e => setFormData({ ...formData, quick_play: { type: 'singleplayer', world: e.target.value } })}
+                                        className="col-span-2 px-3 py-2 bg-black/30 border border-white/10 rounded-lg text-white"
+                                    />
+                                )}
+                                {formData.quick_play?.type === 'multiplayer' && (
+                                    <>
+                                        <input
+                                            type="text"
+                                            placeholder="Host"
+                                            value={formData.quick_play.host}
+                                            onChange={e => setFormData({ ...formData, quick_play: { type: 'multiplayer', host: e.target.value, port: (formData.quick_play as any).port } })}
+                                            className="px-3 py-2 bg-black/30 border border-white/10 rounded-lg text-white"
+                                        />
+                                        <input
+                                            type="number"
+                                            placeholder="25565"
+                                            value={formData.quick_play.port ?? ''}
+                                            onChange={e => setFormData({ ...formData, quick_play: { type: 'multiplayer', host: (formData.quick_play as any).host, port: e.target.value ? parseInt(e.target.value) : null } })}
+                                            className="px-3 py-2 bg-black/30 border border-white/10 rounded-lg text-white"
+                                        />
+                                    </>
+                                )}
+                                {formData.quick_play?.type === 'realm' && (
+                                    <input
+                                        type="text"
+                                        placeholder="Realm ID"
+                                        value={formData.quick_play.realm_id}
+                                        onChange={e => setFormData({ ...formData, quick_play: { type: 'realm', realm_id: e.target.value } })}
+                                        className="col-span-2 px-3 py-2 bg-black/30 border border-white/10 rounded-lg text-white"
+                                    />
+                                )}
+                            </div>
+                        </div>
+                    </div>
+
+                    <div>
+                        <label className="block text-sm font-medium text-gray-400 mb-1">JVM Preset</label>
+                        <select
+                            value={formData.jvm_preset || ''}
+                            onChange={e => setFormData({ ...formData, jvm_preset: e.target.value || null })}
+                            className="w-full px-4 py-2 bg-black/30 border border-white/10 rounded-xl text-white focus:border-indigo-500 outline-none"
+                        >
+                            <option value="">None</option>
+                            <option value="g1">G1 (default)</option>
+                            <option value="aikar">Aikar's flags</option>
+                            <option value="zgc_generational">Generational ZGC (Java 21+)</option>
+                            <option value="low_memory">Low memory</option>
+                        </select>
+                    </div>
+
                     <div>
                         <label className="block text-sm font-medium text-gray-400 mb-1">JVM Arguments</label>
                         <textarea
@@ -40,6 +40,17 @@ export const SettingsScreen: React.FC = () => {
         }
     };
 
+    const handleOfficialProfiles = async (command: 'import_official_profiles' | 'export_official_profiles') => {
+        try {
+            const result: any = await invoke(command);
+            const count = Array.isArray(result) ? result.length : result;
+            setMessage(`${t(command === 'import_official_profiles' ? 'settings.import_official' : 'settings.export_official')}: ${count}`);
+        } catch (e: any) {
+            setMessage(`Error: ${e}`);
+        }
+        setTimeout(() => setMessage(''), 3000);
+    };
+
     const handleLanguageChange = (lang: 'en' | 'es') => {
         setLanguage(lang);
         setSettings({ ...settings, language: lang });
@@ -175,6 +186,29 @@ export const SettingsScreen: React.FC = () => {
                         {/* In a real tauri app we would use open dialog here */}
                     </div>
                     <p className="text-[10px] text-gray-500 font-medium italic">Leave empty to use the system default directory.</p>
+                    <div className="flex items-center justify-between p-4 bg-white/5 rounded-2xl border border-white/5">
+                        <span className="text-sm font-bold text-gray-300">{t('settings.sync_official')}</span>
+                        <button
+                            onClick={() => setSettings({ ...settings, sync_official_profiles: !settings.sync_official_profiles })}
+                            className={`w-12 h-6 rounded-full transition-colors relative ${settings.sync_official_profiles ? 'bg-accent-primary' : 'bg-gray-700'}`}
+                        >
+                            <div className={`absolute top-1 w-4 h-4 bg-white rounded-full transition-all ${settings.sync_official_profiles ? 'left-7' : 'left-1'}`} />
+                        </button>
+                    </div>
+                    <div className="flex gap-4">
+                        <button
+                            onClick={() => handleOfficialProfiles('import_official_profiles')}
+                            className="flex-1 px-4 py-3 bg-white/5 hover:bg-white/10 border border-white/10 rounded-xl text-sm font-bold text-white transition-colors"
+                        >
+                            {t('settings.import_official')}
+                        </button>
+                        <button
+                            onClick={() => handleOfficialProfiles('export_official_profiles')}
+                            className="flex-1 px-4 py-3 bg-white/5 hover:bg-white/10 border border-white/10 rounded-xl text-sm font-bold text-white transition-colors"
+                        >
+                            {t('settings.export_official')}
+                        </button>
+                    </div>
                 </div>
 
                 {/* Java Runtime */}
@@ -196,6 +230,20 @@ export const SettingsScreen: React.FC = () => {
                                 className="flex-1 bg-white/5 border border-white/10 rounded-xl px-4 py-3 text-white focus:outline-none focus:border-accent-primary transition-colors font-mono text-sm"
                             />
                         </div>
+                        <div>
+                            <label className="text-[10px] font-black uppercase text-gray-500 tracking-widest block mb-2">{t('settings.java_provider')}</label>
+                            <div className="flex gap-4">
+                                {(['adoptium', 'mojang'] as const).map((provider) => (
+                                    <button
+                                        key={provider}
+                                        onClick={() => setSettings({ ...settings, java_provider: provider })}
+                                        className={`flex-1 py-3 rounded-xl border font-bold transition-all ${(settings.java_provider || 'adoptium') === provider ? 'bg-accent-primary border-accent-primary text-white' : 'bg-white/5 border-white/10 text-gray-400 hover:bg-white/10'}`}
+                                    >
+                                        {t(`settings.java_provider_${provider}`)}
+                                    </button>
+                                ))}
+                            </div>
+                        </div>
                         <div className="p-4 bg-yellow-500/5 border border-yellow-500/20 rounded-2xl space-y-2">
                             <p className="text-xs font-bold text-yellow-400 uppercase tracking-widest">⚠️ Important for Forge 1.8.9</p>
                             <p className="text-[11px] text-gray-400 leading-relaxed">
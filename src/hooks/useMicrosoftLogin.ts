@@ -9,6 +9,7 @@ export interface MicrosoftAccount {
     xboxToken: string;
     refreshToken: string;
     expiresAt: number;
+    xuid?: string | null;
 }
 
 export interface DeviceCodeData {
@@ -20,7 +20,13 @@ export const translations = {
             height: "Height",
             fullscreen: "Fullscreen",
             game_path: "Game Directory",
+            sync_official: "Keep profiles in sync with the official launcher",
+            import_official: "Import official profiles",
+            export_official: "Export to official launcher",
             java_path: "Java Runtime",
+            java_provider: "Managed runtime source",
+            java_provider_adoptium: "Adoptium (Temurin)",
+            java_provider_mojang: "Mojang (official)",
             language: "Language",
             save: "Save Changes",
             saving: "Saving...",
@@ -113,7 +119,13 @@ export const translations = {
             height: "Alto",
             fullscreen: "Pantalla Completa",
             game_path: "Directorio del Juego",
+            sync_official: "Sincronizar perfiles con el launcher oficial",
+            import_official: "Importar perfiles oficiales",
+            export_official: "Exportar al launcher oficial",
             java_path: "Java Runtime",
+            java_provider: "Origen de los runtimes gestionados",
+            java_provider_adoptium: "Adoptium (Temurin)",
+            java_provider_mojang: "Mojang (oficial)",
             language: "Idioma",
             save: "Guardar Cambios",
             saving: "Guardando...",